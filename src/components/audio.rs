@@ -0,0 +1,8 @@
+use bevy::prelude::Component;
+
+/// Volume setting that scales a sound on top of the master volume.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AudioChannel {
+    Music,
+    Effects,
+}
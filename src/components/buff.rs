@@ -0,0 +1,45 @@
+use bevy::prelude::{Component, Timer, TimerMode};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuffKind {
+    Speed,
+    Damage,
+}
+
+impl BuffKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BuffKind::Speed => "Speed",
+            BuffKind::Damage => "Damage",
+        }
+    }
+}
+
+pub struct Buff {
+    pub(crate) kind: BuffKind,
+    pub(crate) multiplier: f32,
+    pub(crate) timer: Timer,
+}
+
+#[derive(Component, Default)]
+pub struct Buffs(pub(crate) Vec<Buff>);
+
+impl Buffs {
+    /// Applies a buff, refreshing the duration and multiplier of an active one of the same kind.
+    pub(crate) fn add(&mut self, kind: BuffKind, multiplier: f32, seconds: f32) {
+        self.0.retain(|buff| buff.kind != kind);
+        self.0.push(Buff {
+            kind,
+            multiplier,
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+        });
+    }
+
+    pub(crate) fn multiplier(&self, kind: BuffKind) -> f32 {
+        self.0
+            .iter()
+            .filter(|buff| buff.kind == kind)
+            .map(|buff| buff.multiplier)
+            .product()
+    }
+}
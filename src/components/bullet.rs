@@ -1,8 +1,13 @@
-use bevy::math::Vec2;
-use bevy::prelude::{Component, Timer};
+use bevy::prelude::{Component, Entity, Timer};
+
+use crate::components::faction::Faction;
 
 #[derive(Component)]
 pub struct Bullet {
-    pub(crate) velocity: Vec2,
+    pub(crate) owner: Entity,
+    pub(crate) faction: Faction,
     pub(crate) lifetime: Timer,
+    pub(crate) damage: i32,
+    pub(crate) critical: bool,
+    pub(crate) pierce: u32,
 }
@@ -0,0 +1,18 @@
+use bevy::prelude::{Component, Timer, TimerMode};
+
+/// Left behind by a dead enemy; falls over, lies around for a while and fades out.
+#[derive(Component)]
+pub struct Corpse {
+    pub(crate) timer: Timer,
+    /// Angle the body ends up rotated by once it hit the ground.
+    pub(crate) fall_angle: f32,
+}
+
+impl Corpse {
+    pub(crate) fn new(seconds: f32, fall_angle: f32) -> Self {
+        Corpse {
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+            fall_angle,
+        }
+    }
+}
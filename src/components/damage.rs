@@ -0,0 +1,35 @@
+use bevy::prelude::Component;
+use bevy::utils::HashMap;
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum DamageKind {
+    Bullet,
+    Melee,
+    /// Bumping into an enemy.
+    Contact,
+}
+
+/// Reduces incoming damage: per-kind resistances first, then flat armor.
+#[derive(Component, Deserialize, Clone, Default)]
+pub struct Resistances {
+    /// Subtracted from every hit that is not fully resisted, which still deals at least one point.
+    #[serde(default)]
+    pub(crate) armor: i32,
+    /// Fraction of each kind of damage that is ignored, from 0 to 1.
+    #[serde(default)]
+    pub(crate) resist: HashMap<DamageKind, f32>,
+}
+
+impl Resistances {
+    pub(crate) fn apply(&self, amount: i32, kind: DamageKind) -> i32 {
+        let resist = self.resist.get(&kind).copied().unwrap_or(0.).clamp(0., 1.);
+        let resisted = (amount as f32 * (1. - resist)).round() as i32;
+
+        if resisted <= 0 {
+            return 0;
+        }
+        (resisted - self.armor).max(1)
+    }
+}
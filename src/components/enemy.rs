@@ -1,24 +1,25 @@
+use bevy::math::Vec2;
 use bevy::prelude::Component;
 
-use crate::components::health::Health;
-
 #[derive(Component)]
 pub struct Enemy {
-    pub(crate) health: Health,
+    /// Name of the archetype in `archetypes.enemies.json` the enemy was spawned from.
+    pub(crate) archetype: String,
     pub(crate) speed: f32,
     pub(crate) player_spotted: bool,
+    /// Where the player was last seen or heard; enemies search there after losing sight.
+    pub(crate) last_known_player_position: Option<Vec2>,
+    pub(crate) facing: Vec2,
 }
 
 impl Enemy {
-    pub(crate) fn new(max_health: i32, speed: f32) -> Self {
+    pub(crate) fn new(archetype: String, speed: f32) -> Self {
         Enemy {
-            health: Health::new(max_health),
+            archetype,
             speed,
             player_spotted: false,
+            last_known_player_position: None,
+            facing: Vec2::Y,
         }
     }
-
-    pub(crate) fn take_damage(&mut self, amount: i32) {
-        self.health.current = (self.health.current - amount).max(0);
-    }
 }
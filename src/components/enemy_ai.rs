@@ -0,0 +1,76 @@
+use bevy::math::Vec2;
+use bevy::prelude::{Component, Timer, TimerMode};
+use serde::Deserialize;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EnemyState {
+    #[default]
+    Idle,
+    Patrol,
+    Chase,
+    Attack,
+    Search,
+    Flee,
+}
+
+/// Behaviour tuning of an enemy archetype.
+#[derive(Deserialize, Clone, Copy)]
+pub struct AiParams {
+    pub(crate) view_distance: f32,
+    /// Full angle of the vision cone, in degrees.
+    pub(crate) field_of_view: f32,
+    pub(crate) hearing_radius: f32,
+    pub(crate) idle_seconds: f32,
+    pub(crate) patrol_seconds: f32,
+    pub(crate) patrol_radius: f32,
+    pub(crate) search_seconds: f32,
+    /// Fraction of health at or below which the enemy runs away; zero never flees.
+    pub(crate) flee_health_fraction: f32,
+}
+
+impl AiParams {
+    /// How long an enemy stays in a timed state before moving on.
+    fn duration(&self, state: EnemyState) -> f32 {
+        match state {
+            EnemyState::Idle => self.idle_seconds,
+            EnemyState::Patrol => self.patrol_seconds,
+            EnemyState::Search => self.search_seconds,
+            EnemyState::Chase | EnemyState::Attack | EnemyState::Flee => 0.,
+        }
+    }
+}
+
+/// Behaviour state machine of an enemy, every state being driven by its own system.
+#[derive(Component)]
+pub struct EnemyAi {
+    pub(crate) state: EnemyState,
+    pub(crate) state_timer: Timer,
+    pub(crate) params: AiParams,
+    /// Center of the area the enemy patrols.
+    pub(crate) home: Vec2,
+    pub(crate) patrol_target: Option<Vec2>,
+}
+
+impl EnemyAi {
+    pub(crate) fn new(home: Vec2, params: AiParams) -> Self {
+        EnemyAi {
+            state: EnemyState::Idle,
+            state_timer: Timer::from_seconds(params.duration(EnemyState::Idle), TimerMode::Once),
+            params,
+            home,
+            patrol_target: None,
+        }
+    }
+
+    /// Switches to `state`, restarting its timer. Staying in the same state is a no-op.
+    pub(crate) fn transition(&mut self, state: EnemyState) -> bool {
+        if self.state == state {
+            return false;
+        }
+
+        self.state = state;
+        self.state_timer = Timer::from_seconds(self.params.duration(state), TimerMode::Once);
+        self.patrol_target = None;
+        true
+    }
+}
@@ -0,0 +1,35 @@
+use bevy::prelude::{Component, Timer, TimerMode};
+use serde::Deserialize;
+
+/// Bullet fired by a ranged attack.
+#[derive(Deserialize, Clone, Copy)]
+pub struct ProjectileStats {
+    pub(crate) speed: f32,
+    pub(crate) lifetime: f32,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct AttackStats {
+    pub(crate) range: f32,
+    pub(crate) damage: i32,
+    /// Seconds between two attacks.
+    pub(crate) cooldown: f32,
+    /// Ranged attacks shoot this projectile, melee attacks hit directly.
+    #[serde(default)]
+    pub(crate) projectile: Option<ProjectileStats>,
+}
+
+#[derive(Component)]
+pub struct EnemyAttack {
+    pub(crate) stats: AttackStats,
+    pub(crate) cooldown: Timer,
+}
+
+impl EnemyAttack {
+    pub(crate) fn new(stats: AttackStats) -> Self {
+        EnemyAttack {
+            stats,
+            cooldown: Timer::from_seconds(stats.cooldown, TimerMode::Once),
+        }
+    }
+}
@@ -0,0 +1,8 @@
+use bevy::prelude::Component;
+
+/// Side an entity fights on; bullets only hurt the opposing faction.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Faction {
+    Player,
+    Enemy,
+}
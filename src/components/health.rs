@@ -1,3 +1,7 @@
+use bevy::prelude::Component;
+
+/// Hit points of anything that can be damaged; changed through `DamageEvent`s rather than directly.
+#[derive(Component)]
 pub struct Health {
     pub(crate) current: i32,
     pub(crate) max: i32,
@@ -7,4 +11,20 @@ impl Health {
     pub(crate) fn new(max: i32) -> Self {
         Health { current: max, max }
     }
+
+    pub(crate) fn is_dead(&self) -> bool {
+        self.current == 0
+    }
+
+    pub(crate) fn fraction(&self) -> f32 {
+        self.current as f32 / self.max.max(1) as f32
+    }
+
+    pub(crate) fn heal(&mut self, amount: i32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+
+    pub(crate) fn take_damage(&mut self, amount: i32) {
+        self.current = (self.current - amount).max(0);
+    }
 }
@@ -0,0 +1,18 @@
+use bevy::prelude::{Component, Timer, TimerMode};
+
+/// Grace period after a hit during which the entity ignores further damage.
+#[derive(Component)]
+pub struct Invulnerability(pub(crate) Timer);
+
+impl Invulnerability {
+    pub(crate) fn new(seconds: f32) -> Self {
+        let mut timer = Timer::from_seconds(seconds, TimerMode::Once);
+        timer.tick(timer.duration());
+
+        Invulnerability(timer)
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        !self.0.finished()
+    }
+}
@@ -1,6 +1,19 @@
+pub mod audio;
 pub mod health;
+pub mod damage;
+pub mod invulnerability;
 pub mod bullet;
+pub mod corpse;
+pub mod buff;
 pub mod wall;
 pub mod enemy;
+pub mod enemy_ai;
+pub mod enemy_attack;
+pub mod faction;
 pub mod pickup;
 pub mod player;
+pub mod spawner;
+pub mod map;
+pub mod path_follower;
+pub mod perception;
+pub mod weapon;
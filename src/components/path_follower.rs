@@ -0,0 +1,44 @@
+use std::collections::VecDeque;
+
+use bevy::math::Vec2;
+use bevy::prelude::{Component, Timer, TimerMode};
+
+/// Waypoints an actor walks along towards `target`, replanned no more often than its `repath` timer allows.
+#[derive(Component)]
+pub struct PathFollower {
+    pub(crate) target: Option<Vec2>,
+    pub(crate) waypoints: VecDeque<Vec2>,
+    pub(crate) repath: Timer,
+}
+
+impl PathFollower {
+    pub(crate) fn new(repath_seconds: f32) -> Self {
+        let mut repath = Timer::from_seconds(repath_seconds, TimerMode::Once);
+        repath.tick(repath.duration());
+
+        PathFollower {
+            target: None,
+            waypoints: VecDeque::new(),
+            repath,
+        }
+    }
+
+    /// Forgets the current route and allows planning a new one right away.
+    pub(crate) fn clear(&mut self) {
+        self.target = None;
+        self.waypoints.clear();
+        self.repath.tick(self.repath.duration());
+    }
+
+    /// Next waypoint to head for, dropping the ones already within `reach` of `position`.
+    pub(crate) fn next_waypoint(&mut self, position: Vec2, reach: f32) -> Option<Vec2> {
+        while let Some(waypoint) = self.waypoints.front() {
+            if waypoint.distance(position) > reach || self.waypoints.len() == 1 {
+                break;
+            }
+            self.waypoints.pop_front();
+        }
+
+        self.waypoints.front().copied()
+    }
+}
@@ -0,0 +1,19 @@
+use bevy::prelude::Component;
+
+#[derive(Component)]
+pub struct Perception {
+    pub(crate) view_distance: f32,
+    /// Full angle of the vision cone, in radians.
+    pub(crate) field_of_view: f32,
+    pub(crate) hearing_radius: f32,
+}
+
+impl Perception {
+    pub(crate) fn new(view_distance: f32, field_of_view_degrees: f32, hearing_radius: f32) -> Self {
+        Perception {
+            view_distance,
+            field_of_view: field_of_view_degrees.to_radians(),
+            hearing_radius,
+        }
+    }
+}
@@ -1,4 +1,46 @@
-use bevy::prelude::Component;
+use bevy::prelude::{Color, Component};
+use serde::{Deserialize, Serialize};
 
+use crate::components::weapon::WeaponKind;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum PickupKind {
+    Health,
+    Ammo,
+    SpeedBoost,
+    DamageBoost,
+    Currency,
+    Weapon,
+}
+
+impl PickupKind {
+    /// Kinds spawned as random loot; weapons are placed deliberately.
+    pub const LOOT: [PickupKind; 5] = [
+        PickupKind::Health,
+        PickupKind::Ammo,
+        PickupKind::SpeedBoost,
+        PickupKind::DamageBoost,
+        PickupKind::Currency,
+    ];
+
+    pub fn color(&self) -> Color {
+        match self {
+            PickupKind::Health => Color::rgb(0.85, 0.15, 0.2),
+            PickupKind::Ammo => Color::rgb(0.75, 0.6, 0.2),
+            PickupKind::SpeedBoost => Color::rgb(0.2, 0.7, 0.95),
+            PickupKind::DamageBoost => Color::rgb(0.7, 0.25, 0.9),
+            PickupKind::Currency => Color::rgb(0.95, 0.85, 0.1),
+            PickupKind::Weapon => Color::rgb(0.9, 0.9, 0.9),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Pickup {
+    pub(crate) kind: PickupKind,
+}
+
+/// Marks a `PickupKind::Weapon` pickup with the weapon it hands out.
 #[derive(Component)]
-pub struct Pickup;
+pub struct WeaponPickup(pub(crate) WeaponKind);
@@ -1,4 +1,9 @@
+use bevy::math::Vec2;
 use bevy::prelude::Component;
 
-#[derive(Component)]
-pub struct Player;
+#[derive(Component, Default)]
+pub struct Player {
+    pub(crate) zlotowki: u32,
+    /// Velocity the player is trying to move at, in pixels per second.
+    pub(crate) velocity: Vec2,
+}
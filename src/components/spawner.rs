@@ -0,0 +1,13 @@
+use bevy::math::Vec2;
+use bevy::prelude::Component;
+
+/// Area of the map enemies of a wave appear in, placed on the `spawners` object layer.
+#[derive(Component)]
+pub struct Spawner {
+    pub(crate) name: String,
+    pub(crate) half_extents: Vec2,
+}
+
+/// Marks an enemy as part of a wave, numbered from zero.
+#[derive(Component)]
+pub struct WaveEnemy(pub(crate) usize);
@@ -0,0 +1,232 @@
+use bevy::math::Vec2;
+use bevy::prelude::{Color, Component, Timer, TimerMode};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WeaponKind {
+    Pistol,
+    Shotgun,
+    Smg,
+    Rifle,
+}
+
+pub struct WeaponStats {
+    /// Shots per second.
+    pub(crate) fire_rate: f32,
+    pub(crate) automatic: bool,
+    pub(crate) damage: i32,
+    pub(crate) projectile_speed: f32,
+    /// Total cone, in radians, the projectiles of one shot are scattered over.
+    pub(crate) spread: f32,
+    pub(crate) pellets: u32,
+    pub(crate) lifetime: f32,
+    /// How many enemies a projectile passes through before it is destroyed.
+    pub(crate) pierce: u32,
+    /// Chance of a projectile dealing critical damage.
+    pub(crate) crit_chance: f32,
+    pub(crate) magazine_size: u32,
+    pub(crate) max_reserve: u32,
+    pub(crate) reload_seconds: f32,
+}
+
+impl WeaponKind {
+    pub const ALL: [WeaponKind; 4] = [
+        WeaponKind::Pistol,
+        WeaponKind::Shotgun,
+        WeaponKind::Smg,
+        WeaponKind::Rifle,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WeaponKind::Pistol => "Pistol",
+            WeaponKind::Shotgun => "Shotgun",
+            WeaponKind::Smg => "SMG",
+            WeaponKind::Rifle => "Rifle",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            WeaponKind::Pistol => Color::rgb(0.35, 0.35, 0.35),
+            WeaponKind::Shotgun => Color::rgb(0.45, 0.3, 0.15),
+            WeaponKind::Smg => Color::rgb(0.2, 0.2, 0.25),
+            WeaponKind::Rifle => Color::rgb(0.3, 0.35, 0.2),
+        }
+    }
+
+    /// Size of the held weapon sprite, length along the aim direction first.
+    pub fn sprite_size(&self) -> Vec2 {
+        match self {
+            WeaponKind::Pistol => Vec2::new(14., 5.),
+            WeaponKind::Shotgun => Vec2::new(28., 7.),
+            WeaponKind::Smg => Vec2::new(20., 6.),
+            WeaponKind::Rifle => Vec2::new(36., 5.),
+        }
+    }
+
+    pub fn stats(&self) -> WeaponStats {
+        match self {
+            WeaponKind::Pistol => WeaponStats {
+                fire_rate: 4.,
+                automatic: false,
+                damage: 10,
+                projectile_speed: 1000.,
+                spread: 0.03,
+                pellets: 1,
+                lifetime: 1.,
+                pierce: 0,
+                crit_chance: 0.1,
+                magazine_size: 12,
+                max_reserve: 96,
+                reload_seconds: 1.2,
+            },
+            WeaponKind::Shotgun => WeaponStats {
+                fire_rate: 1.2,
+                automatic: false,
+                damage: 8,
+                projectile_speed: 900.,
+                spread: 0.5,
+                pellets: 7,
+                lifetime: 0.4,
+                pierce: 0,
+                crit_chance: 0.05,
+                magazine_size: 6,
+                max_reserve: 36,
+                reload_seconds: 2.2,
+            },
+            WeaponKind::Smg => WeaponStats {
+                fire_rate: 12.,
+                automatic: true,
+                damage: 6,
+                projectile_speed: 1100.,
+                spread: 0.12,
+                pellets: 1,
+                lifetime: 0.8,
+                pierce: 0,
+                crit_chance: 0.05,
+                magazine_size: 30,
+                max_reserve: 180,
+                reload_seconds: 1.8,
+            },
+            WeaponKind::Rifle => WeaponStats {
+                fire_rate: 1.5,
+                automatic: false,
+                damage: 40,
+                projectile_speed: 1800.,
+                spread: 0.,
+                pellets: 1,
+                lifetime: 1.2,
+                pierce: 2,
+                crit_chance: 0.25,
+                magazine_size: 5,
+                max_reserve: 30,
+                reload_seconds: 2.5,
+            },
+        }
+    }
+}
+
+pub struct Weapon {
+    pub(crate) kind: WeaponKind,
+    pub(crate) cooldown: Timer,
+    pub(crate) magazine: u32,
+    pub(crate) reserve: u32,
+    /// Running while the weapon is being reloaded; firing is blocked until it finishes.
+    pub(crate) reload: Option<Timer>,
+}
+
+impl Weapon {
+    pub(crate) fn new(kind: WeaponKind) -> Self {
+        let stats = kind.stats();
+        let mut cooldown = Timer::from_seconds(1. / stats.fire_rate, TimerMode::Once);
+        cooldown.tick(cooldown.duration());
+
+        Weapon {
+            kind,
+            cooldown,
+            magazine: stats.magazine_size,
+            reserve: stats.magazine_size * 2,
+            reload: None,
+        }
+    }
+
+    pub(crate) fn is_reloading(&self) -> bool {
+        self.reload.is_some()
+    }
+
+    pub(crate) fn can_reload(&self) -> bool {
+        !self.is_reloading() && self.reserve > 0 && self.magazine < self.kind.stats().magazine_size
+    }
+
+    pub(crate) fn start_reload(&mut self) {
+        if self.can_reload() {
+            self.reload = Some(Timer::from_seconds(self.kind.stats().reload_seconds, TimerMode::Once));
+        }
+    }
+
+    pub(crate) fn finish_reload(&mut self) {
+        let missing = self.kind.stats().magazine_size - self.magazine;
+        let loaded = missing.min(self.reserve);
+
+        self.magazine += loaded;
+        self.reserve -= loaded;
+        self.reload = None;
+    }
+
+    pub(crate) fn add_ammo(&mut self, rounds: u32) {
+        self.reserve = (self.reserve + rounds).min(self.kind.stats().max_reserve);
+    }
+}
+
+#[derive(Component)]
+pub struct Inventory {
+    pub(crate) weapons: Vec<Weapon>,
+    pub(crate) selected: usize,
+}
+
+impl Inventory {
+    pub(crate) const SLOTS: usize = 4;
+
+    pub(crate) fn new(weapon: Weapon) -> Self {
+        Inventory {
+            weapons: vec![weapon],
+            selected: 0,
+        }
+    }
+
+    pub(crate) fn current(&self) -> &Weapon {
+        &self.weapons[self.selected]
+    }
+
+    pub(crate) fn current_mut(&mut self) -> &mut Weapon {
+        &mut self.weapons[self.selected]
+    }
+
+    /// Switches to the given slot, cancelling a reload in progress. Returns whether the weapon changed.
+    pub(crate) fn select(&mut self, slot: usize) -> bool {
+        if slot >= self.weapons.len() || slot == self.selected {
+            return false;
+        }
+
+        self.current_mut().reload = None;
+        self.selected = slot;
+        true
+    }
+
+    pub(crate) fn cycle(&mut self, step: isize) -> bool {
+        let len = self.weapons.len() as isize;
+        self.select((self.selected as isize + step).rem_euclid(len) as usize)
+    }
+
+    /// Adds a new weapon and selects it, or tops up the reserve ammo of one already carried.
+    pub(crate) fn give(&mut self, kind: WeaponKind) {
+        if let Some(slot) = self.weapons.iter().position(|weapon| weapon.kind == kind) {
+            self.weapons[slot].add_ammo(kind.stats().magazine_size);
+        } else if self.weapons.len() < Self::SLOTS {
+            self.weapons.push(Weapon::new(kind));
+            self.select(self.weapons.len() - 1);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use bevy::math::Vec2;
+
+/// Point to shoot at so a projectile of `projectile_speed` meets a target moving at constant `target_velocity`.
+/// Falls back to the target's current position when the projectile can never catch up.
+pub fn lead_target(shooter: Vec2, target: Vec2, target_velocity: Vec2, projectile_speed: f32) -> Vec2 {
+    let offset = target - shooter;
+
+    // Solve |offset + target_velocity * t| = projectile_speed * t for the earliest positive t.
+    let a = target_velocity.length_squared() - projectile_speed * projectile_speed;
+    let b = 2. * offset.dot(target_velocity);
+    let c = offset.length_squared();
+
+    let times = if a.abs() < f32::EPSILON {
+        // Equal speeds, so the equation is linear and has a single root.
+        [-c / b, f32::NAN]
+    } else {
+        let discriminant = b * b - 4. * a * c;
+        if discriminant < 0. {
+            return target;
+        }
+        let root = discriminant.sqrt();
+        [(-b - root) / (2. * a), (-b + root) / (2. * a)]
+    };
+
+    times
+        .into_iter()
+        .filter(|time| time.is_finite() && *time > 0.)
+        .reduce(f32::min)
+        .map_or(target, |time| target + target_velocity * time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROJECTILE_SPEED: f32 = 100.;
+
+    #[test]
+    fn stationary_target_is_aimed_at_directly() {
+        let target = Vec2::new(30., -40.);
+
+        assert_eq!(lead_target(Vec2::ZERO, target, Vec2::ZERO, PROJECTILE_SPEED), target);
+    }
+
+    #[test]
+    fn crossing_target_is_led_to_where_the_projectile_meets_it() {
+        let target = Vec2::new(100., 0.);
+        let velocity = Vec2::new(0., 50.);
+
+        let aim_point = lead_target(Vec2::ZERO, target, velocity, PROJECTILE_SPEED);
+        let projectile_time = aim_point.length() / PROJECTILE_SPEED;
+        let target_time = (aim_point - target).length() / velocity.length();
+
+        assert!(aim_point.x == 100. && aim_point.y > 0., "aim point {aim_point} is not ahead of the target");
+        assert!((projectile_time - target_time).abs() < 1e-4, "projectile and target arrive apart");
+    }
+
+    #[test]
+    fn target_outrunning_the_projectile_is_aimed_at_directly() {
+        let target = Vec2::new(100., 0.);
+
+        assert_eq!(lead_target(Vec2::ZERO, target, Vec2::new(200., 0.), PROJECTILE_SPEED), target);
+    }
+}
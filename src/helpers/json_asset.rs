@@ -0,0 +1,49 @@
+use std::marker::PhantomData;
+
+use bevy::asset::{Asset, AssetLoader, AsyncReadExt, BoxedFuture, LoadContext};
+use bevy::asset::io::Reader;
+use serde::de::DeserializeOwned;
+
+use crate::helpers::loader_error::LoaderError;
+
+/// Loads any deserializable asset from a JSON file with one of the given extensions, e.g. `pickups.json`.
+///
+/// Bevy matches loaders against everything after the first dot of the file name, so the file has to
+/// be named like `data.pickups.json`; a plain `pickups.json` would only have the `json` extension.
+pub struct JsonAssetLoader<A> {
+    extensions: &'static [&'static str],
+    marker: PhantomData<fn() -> A>,
+}
+
+impl<A> JsonAssetLoader<A> {
+    pub fn new(extensions: &'static [&'static str]) -> Self {
+        JsonAssetLoader {
+            extensions,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<A: Asset + DeserializeOwned> AssetLoader for JsonAssetLoader<A> {
+    type Asset = A;
+    type Settings = ();
+    type Error = LoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+
+            Ok(serde_json::from_slice(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.extensions
+    }
+}
@@ -0,0 +1,35 @@
+use std::fmt::{Display, Formatter};
+
+/// Error reading one of the game's JSON files, be it an asset, a save or a config file.
+#[derive(Debug)]
+pub enum LoaderError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Unsupported(&'static str),
+    UnknownVersion(u64),
+}
+
+impl Display for LoaderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoaderError::Io(error) => write!(f, "could not access file: {error}"),
+            LoaderError::Json(error) => write!(f, "could not parse file: {error}"),
+            LoaderError::Unsupported(reason) => write!(f, "unsupported file: {reason}"),
+            LoaderError::UnknownVersion(version) => write!(f, "unknown file version {version}"),
+        }
+    }
+}
+
+impl std::error::Error for LoaderError {}
+
+impl From<std::io::Error> for LoaderError {
+    fn from(error: std::io::Error) -> Self {
+        LoaderError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for LoaderError {
+    fn from(error: serde_json::Error) -> Self {
+        LoaderError::Json(error)
+    }
+}
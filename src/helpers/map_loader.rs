@@ -1,5 +1,19 @@
+use std::path::PathBuf;
+
+use bevy::asset::{Asset, AssetLoader, AsyncReadExt, BoxedFuture, Handle, LoadContext};
+use bevy::asset::io::Reader;
+use bevy::math::Vec2;
+use bevy::reflect::TypePath;
+use bevy::render::texture::Image;
 use serde::{Deserialize, Serialize};
 
+use crate::helpers::loader_error::LoaderError;
+
+/// Tiled stores tile flipping in the three highest bits of a gid.
+const FLIPPED_HORIZONTALLY_FLAG: u32 = 0x8000_0000;
+const FLIPPED_VERTICALLY_FLAG: u32 = 0x4000_0000;
+const FLIPPED_DIAGONALLY_FLAG: u32 = 0x2000_0000;
+
 #[derive(Serialize, Deserialize)]
 pub struct Map {
     pub height: u32,
@@ -17,14 +31,40 @@ pub struct Map {
 #[derive(Serialize, Deserialize)]
 
 pub struct Layer {
+    #[serde(default)]
     pub data: Vec<u32>,
-    height: u32,
-    width: u32,
-    name: String,
-    opacity: f32,
-    visible: bool,
+    #[serde(default)]
+    pub(crate) height: u32,
+    #[serde(default)]
+    pub(crate) width: u32,
+    pub(crate) name: String,
+    #[serde(rename = "type")]
+    pub(crate) layer_type: String,
+    pub(crate) opacity: f32,
+    pub(crate) visible: bool,
     x: u32,
     y: u32,
+    #[serde(default)]
+    pub(crate) offsetx: f32,
+    #[serde(default)]
+    pub(crate) offsety: f32,
+    #[serde(default)]
+    pub(crate) objects: Vec<MapObject>,
+}
+
+#[derive(Serialize, Deserialize)]
+
+pub struct MapObject {
+    #[serde(default)]
+    pub(crate) name: String,
+    pub(crate) x: f32,
+    pub(crate) y: f32,
+    #[serde(default)]
+    pub(crate) width: f32,
+    #[serde(default)]
+    pub(crate) height: f32,
+    #[serde(default)]
+    pub(crate) rotation: f32,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -43,13 +83,312 @@ pub struct Tileset {
     pub tilewidth: u32,
 }
 
-/*
+/// Rectangle of map cells, `column` and `row` being its top-left cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellRect {
+    pub column: u32,
+    pub row: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A single cell of a tile layer resolved against the map tilesets.
+pub struct TileRef {
+    pub tileset: usize,
+    pub index: usize,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    /// Turned a quarter counter-clockwise around its bottom-left corner, after flipping.
+    pub rotated: bool,
+}
+
+impl Map {
+    pub fn pixel_size(&self) -> Vec2 {
+        Vec2::new(
+            (self.width * self.tilewidth) as f32,
+            (self.height * self.tileheight) as f32,
+        )
+    }
+
+    /// Bottom-left corner of a map cell in map pixels, with the map centered on the origin and y pointing up.
+    pub fn cell_origin(&self, column: u32, row: u32) -> Vec2 {
+        let size = self.pixel_size();
+
+        Vec2::new(
+            (column * self.tilewidth) as f32 - size.x / 2.,
+            size.y / 2. - ((row + 1) * self.tileheight) as f32,
+        )
+    }
+
+    /// Center and half extents of a cell rectangle in map pixels.
+    pub fn cell_rect_bounds(&self, rect: &CellRect) -> (Vec2, Vec2) {
+        let half_extents = Vec2::new(
+            (rect.width * self.tilewidth) as f32,
+            (rect.height * self.tileheight) as f32,
+        ) / 2.;
+        let bottom_left = self.cell_origin(rect.column, rect.row + rect.height - 1);
+
+        (bottom_left + half_extents, half_extents)
+    }
 
-MIGTH BE USEFUL LATER
+    /// Center, half extents and counter-clockwise rotation in radians of a rectangle object in map pixels.
+    pub fn object_bounds(&self, object: &MapObject) -> (Vec2, Vec2, f32) {
+        let size = self.pixel_size();
+        let half_extents = Vec2::new(object.width, object.height) / 2.;
+        let rotation = -object.rotation.to_radians();
 
-pub fn load_map_from_json(path: &str) -> Map {
-    let file_content = std::fs::read_to_string(path).expect("Unable to read file");
-    serde_json::from_str(&file_content).expect("Unable to parse json")
+        // Tiled rotates objects clockwise around their top-left corner.
+        let top_left = Vec2::new(object.x - size.x / 2., size.y / 2. - object.y);
+        let center = top_left + Vec2::from_angle(rotation).rotate(Vec2::new(half_extents.x, -half_extents.y));
+
+        (center, half_extents, rotation)
+    }
+
+    pub fn resolve_gid(&self, gid: u32) -> Option<TileRef> {
+        let id = gid & !(FLIPPED_HORIZONTALLY_FLAG | FLIPPED_VERTICALLY_FLAG | FLIPPED_DIAGONALLY_FLAG);
+        if id == 0 {
+            return None;
+        }
+
+        let (tileset, first) = self
+            .tilesets
+            .iter()
+            .enumerate()
+            .filter(|(_, tileset)| tileset.firstgid <= id)
+            .max_by_key(|(_, tileset)| tileset.firstgid)?;
+
+        let index = (id - first.firstgid) as usize;
+        if index >= first.tilecount as usize {
+            return None;
+        }
+
+        let horizontal = gid & FLIPPED_HORIZONTALLY_FLAG != 0;
+        let vertical = gid & FLIPPED_VERTICALLY_FLAG != 0;
+        let diagonal = gid & FLIPPED_DIAGONALLY_FLAG != 0;
+
+        // Tiled swaps the x and y axes before the other two flips. That is the same as flipping x,
+        // turning a quarter counter-clockwise, and applying the other flips to the opposite axes.
+        let (flip_x, flip_y) = if diagonal {
+            (!vertical, horizontal)
+        } else {
+            (horizontal, vertical)
+        };
+
+        Some(TileRef {
+            tileset,
+            index,
+            flip_x,
+            flip_y,
+            rotated: diagonal,
+        })
+    }
+}
+
+impl Layer {
+    pub fn is_tile_layer(&self) -> bool {
+        self.layer_type == "tilelayer"
+    }
+
+    pub fn is_object_group(&self) -> bool {
+        self.layer_type == "objectgroup"
+    }
+
+    /// Covers every non-empty cell of a tile layer with as few rectangles as possible.
+    pub fn solid_rects(&self) -> Vec<CellRect> {
+        merge_cells(self.width, self.height, |column, row| {
+            self.data
+                .get((row * self.width + column) as usize)
+                .map_or(false, |gid| *gid != 0)
+        })
+    }
 }
 
-*/
+/// Greedily merges solid cells of a grid into rectangles, growing each one right and then down.
+pub fn merge_cells(width: u32, height: u32, is_solid: impl Fn(u32, u32) -> bool) -> Vec<CellRect> {
+    let mut covered = vec![false; (width * height) as usize];
+    let mut rects = Vec::new();
+    let index = |column: u32, row: u32| (row * width + column) as usize;
+
+    for row in 0..height {
+        for column in 0..width {
+            if covered[index(column, row)] || !is_solid(column, row) {
+                continue;
+            }
+
+            let mut rect_width = 1;
+            while column + rect_width < width
+                && !covered[index(column + rect_width, row)]
+                && is_solid(column + rect_width, row)
+            {
+                rect_width += 1;
+            }
+
+            let mut rect_height = 1;
+            while row + rect_height < height
+                && (column..column + rect_width).all(|c| {
+                    !covered[index(c, row + rect_height)] && is_solid(c, row + rect_height)
+                })
+            {
+                rect_height += 1;
+            }
+
+            for r in row..row + rect_height {
+                for c in column..column + rect_width {
+                    covered[index(c, r)] = true;
+                }
+            }
+
+            rects.push(CellRect {
+                column,
+                row,
+                width: rect_width,
+                height: rect_height,
+            });
+        }
+    }
+
+    rects
+}
+
+impl Tileset {
+    pub fn rows(&self) -> u32 {
+        if self.columns == 0 {
+            return 0;
+        }
+        self.tilecount.div_ceil(self.columns)
+    }
+}
+
+/// A Tiled map together with the images of its tilesets, in the same order as `map.tilesets`.
+#[derive(Asset, TypePath)]
+pub struct TiledMap {
+    pub map: Map,
+    #[dependency]
+    pub images: Vec<Handle<Image>>,
+}
+
+/// Loads orthogonal, finite Tiled maps saved as JSON with embedded tilesets.
+#[derive(Default)]
+pub struct TiledMapLoader;
+
+impl AssetLoader for TiledMapLoader {
+    type Asset = TiledMap;
+    type Settings = ();
+    type Error = LoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a Self::Settings,
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+
+            let map = parse_map(&bytes)?;
+            let map_directory = load_context
+                .path()
+                .parent()
+                .map(PathBuf::from)
+                .unwrap_or_default();
+
+            let images = map
+                .tilesets
+                .iter()
+                .map(|tileset| load_context.load(map_directory.join(&tileset.image)))
+                .collect();
+
+            Ok(TiledMap { map, images })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tmj", "json"]
+    }
+}
+
+pub fn parse_map(bytes: &[u8]) -> Result<Map, LoaderError> {
+    let map: Map = serde_json::from_slice(bytes)?;
+
+    if map.infinite {
+        return Err(LoaderError::Unsupported("infinite maps are not supported"));
+    }
+    if map.orientation != "orthogonal" {
+        return Err(LoaderError::Unsupported("only orthogonal maps are supported"));
+    }
+
+    Ok(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use bevy::prelude::*;
+
+    use super::*;
+
+    const ARENA: &str = r#"{
+        "width": 2, "height": 2, "tilewidth": 16, "tileheight": 16, "infinite": false,
+        "version": "1.10", "orientation": "orthogonal", "renderorder": "right-down",
+        "tilesets": [],
+        "layers": [{
+            "name": "ground", "type": "tilelayer", "data": [0, 0, 0, 0],
+            "width": 2, "height": 2, "opacity": 1, "visible": true, "x": 0, "y": 0
+        }]
+    }"#;
+
+    #[test]
+    fn map_saved_as_plain_json_loads() {
+        let directory = std::env::temp_dir().join(format!("stare-bielany-map-test-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("arena.json"), ARENA).unwrap();
+
+        let mut app = App::new();
+        app
+            .add_plugins((MinimalPlugins, AssetPlugin { file_path: directory.to_string_lossy().into_owned(), ..default() }))
+            .init_asset::<TiledMap>()
+            .init_asset_loader::<TiledMapLoader>()
+        ;
+        let handle: Handle<TiledMap> = app.world.resource::<AssetServer>().load("arena.json");
+
+        for _ in 0..200 {
+            app.update();
+            if app.world.resource::<Assets<TiledMap>>().contains(&handle) {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        fs::remove_dir_all(&directory).unwrap();
+
+        let map = &app.world.resource::<Assets<TiledMap>>().get(&handle).expect("map did not load").map;
+        assert_eq!((map.width, map.height), (2, 2));
+        assert_eq!(map.layers[0].name, "ground");
+    }
+
+    /// Merges a grid drawn with `#` for solid cells, one string per row.
+    fn merge(rows: &[&str]) -> Vec<CellRect> {
+        let width = rows[0].len() as u32;
+        merge_cells(width, rows.len() as u32, |column, row| rows[row as usize].as_bytes()[column as usize] == b'#')
+    }
+
+    fn rect(column: u32, row: u32, width: u32, height: u32) -> CellRect {
+        CellRect { column, row, width, height }
+    }
+
+    #[test]
+    fn full_rectangle_merges_into_one_rect() {
+        assert_eq!(merge(&["###", "###"]), vec![rect(0, 0, 3, 2)]);
+    }
+
+    #[test]
+    fn l_shape_merges_into_two_rects() {
+        assert_eq!(merge(&["#..", "#..", "###"]), vec![rect(0, 0, 1, 3), rect(1, 2, 2, 1)]);
+    }
+
+    #[test]
+    fn disjoint_runs_in_one_row_stay_separate() {
+        assert_eq!(merge(&["##.###"]), vec![rect(0, 0, 2, 1), rect(3, 0, 3, 1)]);
+    }
+}
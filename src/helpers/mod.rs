@@ -1 +1,7 @@
+pub mod aim;
+pub mod json_asset;
+pub mod loader_error;
 pub mod map_loader;
+pub mod navigation;
+pub mod save_file;
+pub mod user_data;
@@ -0,0 +1,332 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use bevy::math::{IVec2, Vec2};
+use bevy::prelude::Resource;
+
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+
+/// Walkability grid in world space used by enemies to plan paths around obstacles.
+#[derive(Resource)]
+pub struct NavGrid {
+    pub(crate) width: i32,
+    pub(crate) height: i32,
+    pub(crate) cell_size: f32,
+    /// World position of the bottom-left corner of cell (0, 0).
+    pub(crate) origin: Vec2,
+    blocked: Vec<bool>,
+}
+
+/// Oriented rectangle blocking movement, in world space.
+pub struct Obstacle {
+    pub center: Vec2,
+    pub half_extents: Vec2,
+    pub rotation: f32,
+}
+
+impl NavGrid {
+    pub fn new(origin: Vec2, size: Vec2, cell_size: f32) -> Self {
+        let width = (size.x / cell_size).ceil().max(1.) as i32;
+        let height = (size.y / cell_size).ceil().max(1.) as i32;
+
+        NavGrid {
+            width,
+            height,
+            cell_size,
+            origin,
+            blocked: vec![false; (width * height) as usize],
+        }
+    }
+
+    /// Blocks every cell whose center lies within `clearance` of the obstacle.
+    pub fn block_obstacle(&mut self, obstacle: &Obstacle, clearance: f32) {
+        let axis = Vec2::from_angle(obstacle.rotation);
+        let bounding_half = Vec2::new(
+            axis.x.abs() * obstacle.half_extents.x + axis.y.abs() * obstacle.half_extents.y,
+            axis.y.abs() * obstacle.half_extents.x + axis.x.abs() * obstacle.half_extents.y,
+        ) + Vec2::splat(clearance);
+
+        let min = self.world_to_cell(obstacle.center - bounding_half);
+        let max = self.world_to_cell(obstacle.center + bounding_half);
+
+        for y in min.y.max(0)..=max.y.min(self.height - 1) {
+            for x in min.x.max(0)..=max.x.min(self.width - 1) {
+                let cell = IVec2::new(x, y);
+                let local = Vec2::from_angle(-obstacle.rotation)
+                    .rotate(self.cell_to_world(cell) - obstacle.center);
+                let outside = (local.abs() - obstacle.half_extents).max(Vec2::ZERO);
+
+                if outside.length() <= clearance {
+                    self.set_blocked(cell, true);
+                }
+            }
+        }
+    }
+
+    pub fn set_blocked(&mut self, cell: IVec2, blocked: bool) {
+        if let Some(index) = self.index(cell) {
+            self.blocked[index] = blocked;
+        }
+    }
+
+    pub fn is_walkable(&self, cell: IVec2) -> bool {
+        self.index(cell).map_or(false, |index| !self.blocked[index])
+    }
+
+    pub fn world_to_cell(&self, position: Vec2) -> IVec2 {
+        ((position - self.origin) / self.cell_size).floor().as_ivec2()
+    }
+
+    pub fn cell_to_world(&self, cell: IVec2) -> Vec2 {
+        self.origin + (cell.as_vec2() + Vec2::splat(0.5)) * self.cell_size
+    }
+
+    fn index(&self, cell: IVec2) -> Option<usize> {
+        if cell.x < 0 || cell.y < 0 || cell.x >= self.width || cell.y >= self.height {
+            return None;
+        }
+        Some((cell.y * self.width + cell.x) as usize)
+    }
+
+    /// A* over the grid with 8-way movement that never cuts wall corners.
+    /// Returns world-space waypoints from the cell after `start` up to `goal` itself, or up to the
+    /// center of the nearest walkable cell when `goal` lies inside an obstacle.
+    pub fn find_path(&self, start: Vec2, goal: Vec2) -> Option<Vec<Vec2>> {
+        let start_cell = self.nearest_walkable(self.world_to_cell(start))?;
+        let goal_cell = self.nearest_walkable(self.world_to_cell(goal))?;
+        let goal = if goal_cell == self.world_to_cell(goal) {
+            goal
+        } else {
+            self.cell_to_world(goal_cell)
+        };
+        let start_index = self.index(start_cell)?;
+        let goal_index = self.index(goal_cell)?;
+
+        let mut cost = vec![u32::MAX; self.blocked.len()];
+        let mut came_from = vec![usize::MAX; self.blocked.len()];
+        let mut open = BinaryHeap::new();
+
+        cost[start_index] = 0;
+        open.push(OpenNode {
+            estimate: heuristic(start_cell, goal_cell),
+            cell: start_cell,
+        });
+
+        while let Some(OpenNode { estimate, cell }) = open.pop() {
+            let index = self.index(cell)?;
+            if estimate > cost[index] + heuristic(cell, goal_cell) {
+                continue;
+            }
+            if index == goal_index {
+                return Some(self.build_path(&came_from, start_index, goal_index, goal));
+            }
+
+            for (offset, step_cost) in NEIGHBOURS {
+                let next = cell + offset;
+                if !self.is_walkable(next) {
+                    continue;
+                }
+                if offset.x != 0
+                    && offset.y != 0
+                    && (!self.is_walkable(IVec2::new(next.x, cell.y))
+                        || !self.is_walkable(IVec2::new(cell.x, next.y)))
+                {
+                    continue;
+                }
+
+                let next_index = self.index(next)?;
+                let next_cost = cost[index] + step_cost;
+                if next_cost < cost[next_index] {
+                    cost[next_index] = next_cost;
+                    came_from[next_index] = index;
+                    open.push(OpenNode {
+                        estimate: next_cost + heuristic(next, goal_cell),
+                        cell: next,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    fn build_path(&self, came_from: &[usize], start_index: usize, goal_index: usize, goal: Vec2) -> Vec<Vec2> {
+        let mut path = vec![goal];
+        let mut index = came_from[goal_index];
+
+        while index != start_index && index != usize::MAX {
+            let cell = IVec2::new(index as i32 % self.width, index as i32 / self.width);
+            path.push(self.cell_to_world(cell));
+            index = came_from[index];
+        }
+
+        path.reverse();
+        path
+    }
+
+    /// Searches outwards in growing rings for the closest walkable cell.
+    fn nearest_walkable(&self, cell: IVec2) -> Option<IVec2> {
+        const SEARCH_RADIUS: i32 = 4;
+
+        for radius in 0..=SEARCH_RADIUS {
+            for y in -radius..=radius {
+                for x in -radius..=radius {
+                    if x.abs() != radius && y.abs() != radius {
+                        continue;
+                    }
+
+                    let candidate = cell + IVec2::new(x, y);
+                    if self.is_walkable(candidate) {
+                        return Some(candidate);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+const NEIGHBOURS: [(IVec2, u32); 8] = [
+    (IVec2::new(1, 0), STRAIGHT_COST),
+    (IVec2::new(-1, 0), STRAIGHT_COST),
+    (IVec2::new(0, 1), STRAIGHT_COST),
+    (IVec2::new(0, -1), STRAIGHT_COST),
+    (IVec2::new(1, 1), DIAGONAL_COST),
+    (IVec2::new(1, -1), DIAGONAL_COST),
+    (IVec2::new(-1, 1), DIAGONAL_COST),
+    (IVec2::new(-1, -1), DIAGONAL_COST),
+];
+
+/// Octile distance, admissible for 8-way movement.
+fn heuristic(from: IVec2, to: IVec2) -> u32 {
+    let delta = (to - from).abs();
+    let (long, short) = (delta.x.max(delta.y) as u32, delta.x.min(delta.y) as u32);
+
+    STRAIGHT_COST * (long - short) + DIAGONAL_COST * short
+}
+
+#[derive(PartialEq, Eq)]
+struct OpenNode {
+    estimate: u32,
+    cell: IVec2,
+}
+
+impl Ord for OpenNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed, so the binary max-heap pops the lowest estimate first.
+        other
+            .estimate
+            .cmp(&self.estimate)
+            .then_with(|| (self.cell.x, self.cell.y).cmp(&(other.cell.x, other.cell.y)))
+    }
+}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 10 by 10 grid of one-unit cells with its bottom-left corner at the origin.
+    fn grid(blocked: &[(i32, i32)]) -> NavGrid {
+        let mut grid = NavGrid::new(Vec2::ZERO, Vec2::splat(10.), 1.);
+        for (x, y) in blocked {
+            grid.set_blocked(IVec2::new(*x, *y), true);
+        }
+        grid
+    }
+
+    fn center(grid: &NavGrid, x: i32, y: i32) -> Vec2 {
+        grid.cell_to_world(IVec2::new(x, y))
+    }
+
+    /// Cells visited by `path`, checking that every step moves to a walkable neighbour
+    /// without squeezing diagonally past a blocked cell.
+    fn walk(grid: &NavGrid, start: Vec2, path: &[Vec2]) -> Vec<IVec2> {
+        let mut cell = grid.world_to_cell(start);
+        let mut cells = Vec::new();
+
+        for waypoint in path {
+            let next = grid.world_to_cell(*waypoint);
+            let step = next - cell;
+
+            assert!(grid.is_walkable(next), "path enters blocked cell {next}");
+            assert!(step.abs().max_element() == 1, "path jumps from {cell} to {next}");
+            if step.x != 0 && step.y != 0 {
+                assert!(
+                    grid.is_walkable(IVec2::new(next.x, cell.y)) && grid.is_walkable(IVec2::new(cell.x, next.y)),
+                    "path cuts the corner between {cell} and {next}"
+                );
+            }
+
+            cells.push(next);
+            cell = next;
+        }
+
+        cells
+    }
+
+    #[test]
+    fn path_routes_around_wall_segment() {
+        // Wall across x = 5, leaving a gap at the top.
+        let wall: Vec<(i32, i32)> = (0..8).map(|y| (5, y)).collect();
+        let grid = grid(&wall);
+        let start = center(&grid, 2, 2);
+        let goal = center(&grid, 8, 2);
+
+        let path = grid.find_path(start, goal).expect("goal is reachable through the gap");
+        let cells = walk(&grid, start, &path);
+
+        assert_eq!(path.last(), Some(&goal));
+        assert!(cells.iter().any(|cell| cell.x == 5 && cell.y >= 8), "path does not use the gap: {cells:?}");
+    }
+
+    #[test]
+    fn path_never_cuts_wall_corners() {
+        let grid = grid(&[(1, 0)]);
+        let start = center(&grid, 0, 0);
+        let goal = center(&grid, 1, 1);
+
+        let path = grid.find_path(start, goal).expect("goal is reachable around the corner");
+
+        assert_eq!(walk(&grid, start, &path), vec![IVec2::new(0, 1), IVec2::new(1, 1)]);
+    }
+
+    #[test]
+    fn unreachable_goal_has_no_path() {
+        let ring: Vec<(i32, i32)> = (6..=8)
+            .flat_map(|x| (6..=8).map(move |y| (x, y)))
+            .filter(|cell| *cell != (7, 7))
+            .collect();
+        let grid = grid(&ring);
+
+        assert_eq!(grid.find_path(center(&grid, 1, 1), center(&grid, 7, 7)), None);
+    }
+
+    #[test]
+    fn goal_inside_obstacle_ends_next_to_it() {
+        let grid = grid(&[(7, 7)]);
+        let start = center(&grid, 1, 1);
+
+        let path = grid.find_path(start, center(&grid, 7, 7)).expect("a cell next to the goal is reachable");
+        let cells = walk(&grid, start, &path);
+        let end = *cells.last().unwrap();
+
+        assert_eq!((end - IVec2::new(7, 7)).abs().max_element(), 1);
+        assert_eq!(path.last(), Some(&grid.cell_to_world(end)));
+    }
+
+    #[test]
+    fn path_to_own_cell_is_just_the_goal() {
+        let grid = grid(&[]);
+        let position = center(&grid, 4, 4);
+
+        assert_eq!(grid.find_path(position, position), Some(vec![position]));
+    }
+}
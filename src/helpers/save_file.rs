@@ -0,0 +1,183 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::components::pickup::PickupKind;
+use crate::components::weapon::WeaponKind;
+use crate::helpers::loader_error::LoaderError;
+use crate::helpers::user_data::data_dir;
+
+/// Bumped whenever the save layout changes; older saves go through `MIGRATIONS` first.
+pub const SAVE_VERSION: u32 = 2;
+
+/// `MIGRATIONS[i]` turns a version `i + 1` save into a version `i + 2` one.
+const MIGRATIONS: &[fn(&mut Value)] = &[add_spawn_queue];
+
+// Bumping `SAVE_VERSION` without a migration would leave older saves unreadable.
+const _: () = assert!(MIGRATIONS.len() + 1 == SAVE_VERSION as usize);
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct SavedWeapon {
+    pub(crate) kind: WeaponKind,
+    pub(crate) magazine: u32,
+    pub(crate) reserve: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedPlayer {
+    pub(crate) position: [f32; 2],
+    pub(crate) health: i32,
+    pub(crate) zlotowki: u32,
+    pub(crate) weapons: Vec<SavedWeapon>,
+    pub(crate) selected_weapon: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedEnemy {
+    pub(crate) archetype: String,
+    pub(crate) position: [f32; 2],
+    pub(crate) health: i32,
+    /// Index of the wave the enemy belongs to, if it was spawned by one.
+    pub(crate) wave: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedPickup {
+    pub(crate) kind: PickupKind,
+    pub(crate) weapon: Option<WeaponKind>,
+    pub(crate) position: [f32; 2],
+}
+
+/// Enemy of a started wave that had not spawned yet.
+#[derive(Serialize, Deserialize)]
+pub struct SavedSpawn {
+    pub(crate) wave: usize,
+    pub(crate) enemy: String,
+    pub(crate) spawner: Option<String>,
+}
+
+/// Snapshot of a run. Only what is still in the world is stored, so collected pickups and killed
+/// enemies stay gone after loading.
+#[derive(Serialize, Deserialize)]
+pub struct SaveGame {
+    pub(crate) version: u32,
+    pub(crate) map: String,
+    pub(crate) player: SavedPlayer,
+    pub(crate) points: u32,
+    pub(crate) kills: u32,
+    /// Number of waves started so far.
+    pub(crate) waves_started: usize,
+    /// Enemies of started waves still waiting to spawn, in spawn order.
+    pub(crate) spawn_queue: Vec<SavedSpawn>,
+    pub(crate) enemies: Vec<SavedEnemy>,
+    pub(crate) pickups: Vec<SavedPickup>,
+}
+
+pub fn save_path() -> PathBuf {
+    data_dir().join("save.json")
+}
+
+pub fn write_save(path: &Path, save: &SaveGame) -> Result<(), LoaderError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    // Written next to the old save first, so a crash mid-write cannot corrupt it.
+    let temporary = path.with_extension("json.tmp");
+    fs::write(&temporary, serde_json::to_vec_pretty(save)?)?;
+    fs::rename(temporary, path)?;
+    Ok(())
+}
+
+pub fn read_save(path: &Path) -> Result<SaveGame, LoaderError> {
+    let mut value: Value = serde_json::from_slice(&fs::read(path)?)?;
+    migrate(&mut value)?;
+
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Brings a save of any known version up to `SAVE_VERSION`.
+fn migrate(value: &mut Value) -> Result<(), LoaderError> {
+    let version = value.get("version").and_then(Value::as_u64).unwrap_or(0);
+    if version == 0 || version > SAVE_VERSION as u64 {
+        return Err(LoaderError::UnknownVersion(version));
+    }
+
+    for migration in MIGRATIONS.iter().skip(version as usize - 1) {
+        migration(value);
+    }
+    value["version"] = Value::from(SAVE_VERSION);
+    Ok(())
+}
+
+/// Version 1 did not record the enemies still queued to spawn.
+fn add_spawn_queue(value: &mut Value) {
+    value["spawn_queue"] = Value::Array(Vec::new());
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn version_1_save() -> Value {
+        json!({
+            "version": 1,
+            "map": "stare-bielany.tmj",
+            "player": {
+                "position": [10.0, -20.0],
+                "health": 75,
+                "zlotowki": 12,
+                "weapons": [{ "kind": "shotgun", "magazine": 2, "reserve": 16 }],
+                "selected_weapon": 0
+            },
+            "points": 300,
+            "kills": 6,
+            "waves_started": 2,
+            "enemies": [{ "archetype": "grunt", "position": [100.0, 50.0], "health": 20, "wave": 1 }],
+            "pickups": []
+        })
+    }
+
+    #[test]
+    fn version_1_save_migrates_to_current_version() {
+        let mut value = version_1_save();
+        migrate(&mut value).unwrap();
+
+        let save: SaveGame = serde_json::from_value(value).unwrap();
+        assert_eq!(save.version, SAVE_VERSION);
+        assert!(save.spawn_queue.is_empty());
+        assert_eq!(save.player.health, 75);
+        assert_eq!(save.waves_started, 2);
+        assert_eq!(save.enemies[0].wave, Some(1));
+    }
+
+    #[test]
+    fn version_1_save_file_round_trips() {
+        let path = std::env::temp_dir().join(format!("stare-bielany-save-test-{}.json", std::process::id()));
+        fs::write(&path, serde_json::to_vec(&version_1_save()).unwrap()).unwrap();
+
+        let save = read_save(&path).unwrap();
+        write_save(&path, &save).unwrap();
+        let reread = read_save(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(reread.version, SAVE_VERSION);
+        assert_eq!(reread.points, 300);
+        assert_eq!(reread.player.weapons[0].kind, WeaponKind::Shotgun);
+        assert_eq!(reread.enemies[0].archetype, "grunt");
+    }
+
+    #[test]
+    fn unknown_versions_are_rejected() {
+        for version in [0, SAVE_VERSION + 1] {
+            let mut value = version_1_save();
+            value["version"] = Value::from(version);
+
+            assert!(matches!(migrate(&mut value), Err(LoaderError::UnknownVersion(_))));
+        }
+    }
+}
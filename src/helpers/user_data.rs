@@ -0,0 +1,101 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bevy::log::warn;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::helpers::loader_error::LoaderError;
+
+const APP_DIR: &str = "stare-bielany";
+
+/// Per-user directory for saves: `%APPDATA%` on Windows, `~/Library/Application Support` on macOS
+/// and `$XDG_DATA_HOME` (or `~/.local/share`) elsewhere. Falls back to the working directory.
+pub fn data_dir() -> PathBuf {
+    user_dir("Library/Application Support", "XDG_DATA_HOME", ".local/share")
+}
+
+/// Per-user directory for configuration: `%APPDATA%` on Windows, `~/Library/Preferences` on macOS
+/// and `$XDG_CONFIG_HOME` (or `~/.config`) elsewhere. Falls back to the working directory.
+pub fn config_dir() -> PathBuf {
+    user_dir("Library/Preferences", "XDG_CONFIG_HOME", ".config")
+}
+
+/// The game's directory inside `macos_dir` of the home directory on macOS, and inside
+/// `xdg_variable` or else `xdg_fallback` of the home directory on other Unix systems.
+fn user_dir(macos_dir: &str, xdg_variable: &str, xdg_fallback: &str) -> PathBuf {
+    let home = || env::var_os("HOME").map(PathBuf::from);
+    let base = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home().map(|home| home.join(macos_dir))
+    } else {
+        env::var_os(xdg_variable)
+            .map(PathBuf::from)
+            .or_else(|| home().map(|home| home.join(xdg_fallback)))
+    };
+
+    base.unwrap_or_else(|| PathBuf::from(".")).join(APP_DIR)
+}
+
+/// Reads a JSON config file, or `None` when it is missing or broken so the caller falls back to
+/// its defaults. A broken file is reported rather than silently ignored.
+pub fn read_config<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let bytes = fs::read(path).ok()?;
+
+    serde_json::from_slice(&bytes)
+        .map_err(|error| warn!("ignoring broken config file {}: {error}", path.display()))
+        .ok()
+}
+
+/// Writes a JSON config file, creating its directory first. Failing to save a config only costs
+/// the player their changes, so it is reported instead of returned.
+pub fn write_config<T: Serialize>(path: &Path, value: &T) {
+    let write = || -> Result<(), LoaderError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_vec_pretty(value)?)?;
+        Ok(())
+    };
+
+    if let Err(error) = write() {
+        warn!("could not write config file {}: {error}", path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Config file in a directory of its own, as tests run in parallel.
+    fn temp_path(test: &str) -> PathBuf {
+        env::temp_dir()
+            .join(format!("stare-bielany-config-test-{}-{test}", std::process::id()))
+            .join("config.json")
+    }
+
+    #[test]
+    fn config_round_trips_through_a_new_directory() {
+        let path = temp_path("round-trip");
+        write_config(&path, &[1, 2, 3]);
+        let read: Option<Vec<i32>> = read_config(&path);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        assert_eq!(read, Some(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn missing_and_broken_configs_read_as_none() {
+        let path = temp_path("broken");
+        assert_eq!(read_config::<Vec<i32>>(&path), None);
+
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "[1, 2,").unwrap();
+        let read = read_config::<Vec<i32>>(&path);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        assert_eq!(read, None);
+    }
+}
@@ -6,9 +6,24 @@ use plugins::player::PlayerPlugin;
 use setup::camera::camera_setup;
 
 use crate::plugins::bullet::BulletPlugin;
-use crate::plugins::cursor_position::CursorPositionPlugin;
+use crate::plugins::aim::AimPlugin;
+use crate::plugins::damage::DamagePlugin;
+use crate::plugins::damage_feedback::DamageFeedbackPlugin;
 use crate::plugins::enemy::EnemyPlugin;
+use crate::plugins::enemy_ai::EnemyAiPlugin;
+use crate::plugins::game_state::GameStatePlugin;
+use crate::plugins::hud::HudPlugin;
+use crate::plugins::input::{ActionState, InputPlugin};
+use crate::plugins::main_menu::MainMenuPlugin;
+use crate::plugins::map::MapPlugin;
+use crate::plugins::save::SavePlugin;
+use crate::plugins::score::ScorePlugin;
+use crate::plugins::settings::SettingsPlugin;
 use crate::plugins::wall::WallPlugin;
+use crate::plugins::wave::WavePlugin;
+use crate::plugins::weapon::WeaponPlugin;
+use crate::resources::input_bindings::InputAction;
+use crate::resources::settings::Settings;
 
 mod components;
 mod helpers;
@@ -18,38 +33,61 @@ mod setup;
 mod systems;
 
 fn main() {
+    let settings = Settings::load();
+
     App::new()
-        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
+        .add_plugins(
+            DefaultPlugins
+                .set(ImagePlugin::default_nearest())
+                .set(WindowPlugin {
+                    primary_window: Some(settings.window()),
+                    ..default()
+                }),
+        )
+        .insert_resource(settings)
+        .add_plugins(GameStatePlugin)
+        .add_plugins(InputPlugin)
+        .add_plugins(SettingsPlugin)
+        .add_plugins(MainMenuPlugin)
         .add_systems(Startup, camera_setup)
         .add_plugins(PhysicsPlugin)
-        .add_plugins(CursorPositionPlugin)
+        .add_plugins(MapPlugin)
+        .add_plugins(AimPlugin)
         .add_plugins(PlayerPlugin)
+        .add_plugins(WeaponPlugin)
         .add_plugins(BulletPlugin)
+        .add_plugins(DamagePlugin)
         .add_plugins(WallPlugin)
         .add_plugins(EnemyPlugin)
+        .add_plugins(EnemyAiPlugin)
+        .add_plugins(WavePlugin)
         .add_plugins(PickupPlugin)
+        .add_plugins(ScorePlugin)
+        .add_plugins(HudPlugin)
+        .add_plugins(DamageFeedbackPlugin)
+        .add_plugins(SavePlugin)
         .add_systems(Update, camera_movement_system)
         // .add_systems(Update, print_camera_position)
         .run();
 }
 
 fn camera_movement_system(
-    keyboard_input: Res<Input<KeyCode>>,
+    action_state: Res<ActionState>,
     mut query: Query<&mut Transform, With<Camera>>,
 ) {
     for mut transform in query.iter_mut() {
         let mut direction = Vec3::ZERO;
 
-        if keyboard_input.pressed(KeyCode::Left) {
+        if action_state.pressed(InputAction::PanLeft) {
             direction.x -= 1.0;
         }
-        if keyboard_input.pressed(KeyCode::Right) {
+        if action_state.pressed(InputAction::PanRight) {
             direction.x += 1.0;
         }
-        if keyboard_input.pressed(KeyCode::Up) {
+        if action_state.pressed(InputAction::PanUp) {
             direction.y += 1.0;
         }
-        if keyboard_input.pressed(KeyCode::Down) {
+        if action_state.pressed(InputAction::PanDown) {
             direction.y -= 1.0;
         }
 
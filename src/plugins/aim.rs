@@ -0,0 +1,264 @@
+use bevy::input::mouse::MouseMotion;
+use bevy::prelude::*;
+use bevy::window::{CursorGrabMode, PrimaryWindow};
+
+use crate::components::enemy::Enemy;
+use crate::components::health::Health;
+use crate::components::player::Player;
+use crate::plugins::input::{ActionState, InputDevice};
+use crate::resources::app_state::AppState;
+use crate::resources::constants::{
+    AIM_ASSIST_ANGLE, AIM_ASSIST_RANGE, AIM_ASSIST_STRENGTH, GAMEPAD_AIM_DISTANCE,
+};
+use crate::resources::settings::Settings;
+use crate::setup::camera::MainCamera;
+use crate::systems::cleanup::despawn_with;
+
+const CROSSHAIR_ARM_LENGTH: f32 = 8.;
+const CROSSHAIR_THICKNESS: f32 = 2.;
+/// Empty space between the aim point and the inner end of each arm.
+const CROSSHAIR_GAP: f32 = 4.;
+
+/// Where the player aims, worked out once per frame so turning and shooting always agree.
+#[derive(Resource)]
+pub struct AimState {
+    /// Unit vector from the player towards `target`.
+    pub(crate) direction: Vec2,
+    /// World position under the crosshair.
+    pub(crate) target: Vec2,
+    pub(crate) source: InputDevice,
+}
+
+impl Default for AimState {
+    fn default() -> Self {
+        AimState {
+            direction: Vec2::Y,
+            target: Vec2::ZERO,
+            source: InputDevice::default(),
+        }
+    }
+}
+
+/// Systems reading `AimState` run after this set.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AimSystems;
+
+#[derive(Component)]
+pub struct Crosshair;
+
+pub struct AimPlugin;
+
+impl Plugin for AimPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<AimState>()
+            .add_systems(OnExit(AppState::Loading), spawn_crosshair)
+            .add_systems(OnEnter(AppState::MainMenu), despawn_with::<Crosshair>)
+            .add_systems(OnEnter(AppState::Playing), show_crosshair)
+            .add_systems(OnExit(AppState::Playing), hide_crosshair)
+            .add_systems(
+                Update,
+                (update_aim, move_crosshair)
+                    .chain()
+                    .in_set(AimSystems)
+                    .run_if(in_state(AppState::Playing)),
+            )
+        ;
+    }
+}
+
+fn update_aim(
+    mut aim: ResMut<AimState>,
+    // The stick springs back to the centre, so its last direction is kept to aim with meanwhile.
+    mut stick_direction: Local<Option<Vec2>>,
+    // The mouse moves a virtual aim point by its motion scaled by the sensitivity, instead of the
+    // crosshair following the hidden system cursor. It is kept relative to the player, so walking
+    // carries it along.
+    mut mouse_offset: Local<Option<Vec2>>,
+    mut mouse_motion_events: EventReader<MouseMotion>,
+    action_state: Res<ActionState>,
+    settings: Res<Settings>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    player_query: Query<&Transform, With<Player>>,
+    enemy_query: Query<(&Transform, &Health), (With<Enemy>, Without<Player>)>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let player_position = player_transform.translation.truncate();
+
+    if action_state.aim_stick != Vec2::ZERO {
+        *stick_direction = Some(action_state.aim_stick.normalize());
+    }
+
+    // The visible part of the world, and how much of it one pixel of mouse motion covers.
+    let view = camera_query.get_single().ok().and_then(|(camera, camera_transform)| {
+        let size = camera.logical_viewport_size()?;
+        let top_left = camera.viewport_to_world_2d(camera_transform, Vec2::ZERO)?;
+        let bottom_right = camera.viewport_to_world_2d(camera_transform, size)?;
+        let view = Rect::from_corners(top_left, bottom_right);
+        Some((view, view.width() / size.x))
+    });
+
+    let motion: Vec2 = mouse_motion_events.read().map(|event| event.delta).sum();
+    if motion != Vec2::ZERO {
+        let world_per_pixel = view.map_or(1., |(_, world_per_pixel)| world_per_pixel);
+        let offset = mouse_offset.unwrap_or(aim.direction * GAMEPAD_AIM_DISTANCE);
+        // Screen y grows downwards, world y upwards.
+        let motion = Vec2::new(motion.x, -motion.y) * settings.mouse_sensitivity * world_per_pixel;
+        *mouse_offset = Some(offset + motion);
+    }
+    // However far the mouse goes or the player walks, the crosshair stays on screen.
+    if let (Some(offset), Some((view, _))) = (mouse_offset.as_mut(), view) {
+        *offset = (player_position + *offset).clamp(view.min, view.max) - player_position;
+    }
+
+    aim.source = action_state.device;
+    let offset = match aim.source {
+        InputDevice::KeyboardMouse => *mouse_offset,
+        InputDevice::Gamepad => stick_direction.map(|direction| direction * GAMEPAD_AIM_DISTANCE),
+    };
+    // Until there is something to aim by, or while the cursor sits on the player, keep the old direction.
+    let offset = offset
+        .filter(|offset| *offset != Vec2::ZERO)
+        .unwrap_or(aim.direction * GAMEPAD_AIM_DISTANCE);
+
+    let mut direction = offset.normalize();
+    if aim.source == InputDevice::Gamepad {
+        direction = assist_aim(player_position, direction, &enemy_query);
+    }
+
+    aim.direction = direction;
+    aim.target = player_position + direction * offset.length();
+}
+
+/// Bends a stick aim part of the way towards the living enemy closest to it in angle, if one is
+/// near enough and only slightly off. The mouse is precise enough to go without.
+fn assist_aim(
+    player_position: Vec2,
+    direction: Vec2,
+    enemy_query: &Query<(&Transform, &Health), (With<Enemy>, Without<Player>)>,
+) -> Vec2 {
+    enemy_query
+        .iter()
+        .filter(|(_, health)| !health.is_dead())
+        .map(|(transform, _)| transform.translation.truncate() - player_position)
+        .filter(|to_enemy| *to_enemy != Vec2::ZERO && to_enemy.length() <= AIM_ASSIST_RANGE)
+        .map(|to_enemy| direction.angle_between(to_enemy))
+        .filter(|angle| angle.abs() <= AIM_ASSIST_ANGLE)
+        .min_by(|a, b| a.abs().total_cmp(&b.abs()))
+        .map_or(direction, |angle| Vec2::from_angle(angle * AIM_ASSIST_STRENGTH).rotate(direction))
+}
+
+fn spawn_crosshair(mut commands: Commands) {
+    commands
+        .spawn((
+            SpatialBundle {
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            Crosshair,
+        ))
+        .with_children(|parent| {
+            for arm in [Vec2::X, Vec2::Y, Vec2::NEG_X, Vec2::NEG_Y] {
+                let size = arm.abs() * (CROSSHAIR_ARM_LENGTH - CROSSHAIR_THICKNESS) + CROSSHAIR_THICKNESS;
+
+                parent.spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgba(1., 1., 1., 0.9),
+                        custom_size: Some(size),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(
+                        (arm * (CROSSHAIR_GAP + CROSSHAIR_ARM_LENGTH / 2.)).extend(0.),
+                    ),
+                    ..default()
+                });
+            }
+        });
+}
+
+fn move_crosshair(aim: Res<AimState>, mut crosshair_query: Query<&mut Transform, With<Crosshair>>) {
+    for mut transform in crosshair_query.iter_mut() {
+        transform.translation = aim.target.extend(30.);
+    }
+}
+
+/// The crosshair stands in for the system cursor while playing, which is kept inside the window
+/// so clicks cannot land on other windows. Mouse motion still arrives at the window's edge.
+fn show_crosshair(
+    mut crosshair_query: Query<&mut Visibility, With<Crosshair>>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    set_crosshair_visible(true, &mut crosshair_query, &mut window_query);
+}
+
+fn hide_crosshair(
+    mut crosshair_query: Query<&mut Visibility, With<Crosshair>>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    set_crosshair_visible(false, &mut crosshair_query, &mut window_query);
+}
+
+fn set_crosshair_visible(
+    visible: bool,
+    crosshair_query: &mut Query<&mut Visibility, With<Crosshair>>,
+    window_query: &mut Query<&mut Window, With<PrimaryWindow>>,
+) {
+    for mut visibility in crosshair_query.iter_mut() {
+        *visibility = if visible { Visibility::Visible } else { Visibility::Hidden };
+    }
+    for mut window in window_query.iter_mut() {
+        window.cursor.visible = !visible;
+        window.cursor.grab_mode = if visible { CursorGrabMode::Confined } else { CursorGrabMode::None };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aim_app(mouse_sensitivity: f32) -> App {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_event::<MouseMotion>()
+            .init_resource::<ActionState>()
+            .init_resource::<AimState>()
+            .insert_resource(Settings {
+                mouse_sensitivity,
+                ..default()
+            })
+            .add_systems(Update, update_aim)
+        ;
+        app.world.spawn((Player::default(), Transform::default()));
+        app
+    }
+
+    fn move_mouse(app: &mut App, delta: Vec2) -> Vec2 {
+        app.world.send_event(MouseMotion { delta });
+        app.update();
+        app.world.resource::<AimState>().target
+    }
+
+    #[test]
+    fn mouse_motion_moves_aim_point_scaled_by_sensitivity() {
+        let mut app = aim_app(2.);
+        let start = Vec2::Y * GAMEPAD_AIM_DISTANCE;
+
+        let target = move_mouse(&mut app, Vec2::new(10., 0.));
+        assert!(target.abs_diff_eq(start + Vec2::new(20., 0.), 1e-3), "aim point at {target}");
+
+        let target = move_mouse(&mut app, Vec2::new(0., 15.));
+        assert!(target.abs_diff_eq(start + Vec2::new(20., -30.), 1e-3), "aim point at {target}");
+    }
+
+    #[test]
+    fn aim_point_stays_put_without_mouse_motion() {
+        let mut app = aim_app(1.);
+        let target = move_mouse(&mut app, Vec2::new(-40., 0.));
+        app.update();
+
+        assert_eq!(app.world.resource::<AimState>().target, target);
+    }
+}
@@ -1,127 +1,133 @@
 use bevy::app::{App, Update};
+use bevy::math::Vec2;
 use bevy::prelude::{
-    Commands, Entity, EventReader, Plugin, Query, Res, Resource, Time, Timer, Transform, With,
+    in_state, Commands, Entity, EventReader, EventWriter, IntoSystemConfigs, OnEnter, Parent,
+    Plugin, Quat, Query, Res, Time, Transform, TransformBundle, With,
 };
-use bevy::time::TimerMode;
+use bevy_rapier2d::dynamics::{LockedAxes, RigidBody, Velocity};
+use bevy_rapier2d::geometry::{Collider, Sensor};
 use bevy_rapier2d::pipeline::CollisionEvent;
 
 use crate::components::bullet::Bullet;
-use crate::components::enemy::Enemy;
+use crate::components::damage::DamageKind;
+use crate::components::faction::Faction;
+use crate::components::health::Health;
 use crate::components::wall::Wall;
-
-#[derive(Default, Resource)]
-pub struct BulletSpawnTimer(pub(crate) Timer);
+use crate::plugins::damage::DamageEvent;
+use crate::resources::app_state::AppState;
+use crate::systems::cleanup::despawn_with;
 
 pub struct BulletPlugin;
 
 impl Plugin for BulletPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(BulletSpawnTimer(Timer::from_seconds(
-            0.1,
-            TimerMode::Repeating,
-        )))
-        .add_systems(Update, destroy_expired_bullets)
-        .add_systems(Update, move_bullets)
-        .add_systems(Update, listen_collision_events);
+        app.add_systems(OnEnter(AppState::MainMenu), despawn_with::<Bullet>)
+            .add_systems(
+                Update,
+                (destroy_expired_bullets, listen_collision_events).run_if(in_state(AppState::Playing)),
+            );
     }
 }
 
+pub fn spawn_bullet(
+    commands: &mut Commands,
+    position: Vec2,
+    velocity: Vec2,
+    bullet: Bullet,
+) {
+    let angle = velocity.y.atan2(velocity.x) + std::f32::consts::FRAC_PI_2;
+
+    commands
+        .spawn(Collider::capsule_y(5., 1.5))
+        .insert(RigidBody::Dynamic)
+        .insert(Sensor)
+        .insert(Velocity::linear(velocity))
+        .insert(LockedAxes::ROTATION_LOCKED)
+        .insert(bullet)
+        .insert(TransformBundle::from(
+            Transform::from_xyz(position.x, position.y, 0.).with_rotation(Quat::from_rotation_z(angle)),
+        ));
+}
+
 fn listen_collision_events(
     mut commands: Commands,
     mut collision_events: EventReader<CollisionEvent>,
-    bullet_query: Query<Entity, With<Bullet>>,
-    mut enemy_query: Query<(Entity, &mut Enemy)>,
-    wall_query: Query<Entity, With<Wall>>,
+    mut bullet_query: Query<&mut Bullet>,
+    faction_query: Query<&Faction>,
+    parent_query: Query<&Parent>,
+    health_query: Query<&Health>,
+    wall_query: Query<(), With<Wall>>,
+    mut damage_events: EventWriter<DamageEvent>,
 ) {
+    // A bullet can touch several colliders in one frame but is only despawned once.
+    let mut spent = Vec::new();
+
     for collision_event in collision_events.read() {
-        match collision_event {
-            CollisionEvent::Started(entity1, entity2, _) => {
-                handle_bullet_collision(
-                    &mut commands,
-                    entity1,
-                    entity2,
-                    &bullet_query,
-                    &mut enemy_query,
-                    &wall_query,
-                );
-            }
-            _ => {}
+        let CollisionEvent::Started(entity1, entity2, _) = collision_event else {
+            continue;
+        };
+        let (bullet_entity, other) = if bullet_query.contains(*entity1) {
+            (*entity1, *entity2)
+        } else if bullet_query.contains(*entity2) {
+            (*entity2, *entity1)
+        } else {
+            continue;
+        };
+        if spent.contains(&bullet_entity) {
+            continue;
         }
-    }
-}
+        let Ok(mut bullet) = bullet_query.get_mut(bullet_entity) else {
+            continue;
+        };
 
-pub fn handle_bullet_collision(
-    commands: &mut Commands,
-    entity1: &Entity,
-    entity2: &Entity,
-    bullet_query: &Query<Entity, With<Bullet>>,
-    enemy_query: &mut Query<(Entity, &mut Enemy)>,
-    wall_query: &Query<Entity, With<Wall>>,
-) {
-    if let Ok(bullet_entity) = get_bullet_entity(entity1, entity2, bullet_query) {
-        if process_bullet_enemy_collision(bullet_entity, entity1, entity2, commands, enemy_query)
-            || process_bullet_wall_collision(bullet_entity, entity1, entity2, commands, wall_query)
-        {
-            return;
+        if wall_query.contains(other) {
+            spent.push(bullet_entity);
+            continue;
         }
-    }
-}
 
-fn get_bullet_entity<'a>(
-    entity1: &'a Entity,
-    entity2: &'a Entity,
-    bullet_query: &'a Query<Entity, With<Bullet>>,
-) -> Result<&'a Entity, ()> {
-    if bullet_query.get(*entity1).is_ok() {
-        Ok(entity1)
-    } else if bullet_query.get(*entity2).is_ok() {
-        Ok(entity2)
-    } else {
-        Err(())
-    }
-}
-
-fn process_bullet_enemy_collision(
-    bullet_entity: &Entity,
-    entity1: &Entity,
-    entity2: &Entity,
-    commands: &mut Commands,
-    enemy_query: &mut Query<(Entity, &mut Enemy)>,
-) -> bool {
-    let (bullet, enemy) = if enemy_query.get_mut(*entity1).is_ok() {
-        (bullet_entity, entity1)
-    } else if enemy_query.get_mut(*entity2).is_ok() {
-        (bullet_entity, entity2)
-    } else {
-        return false;
-    };
+        let Some(target) = hit_target(other, &health_query, &parent_query) else {
+            continue;
+        };
+        // Dead targets linger for a moment before being replaced by a corpse; let bullets pass through.
+        if target == bullet.owner
+            || faction_query.get(target).ok() == Some(&bullet.faction)
+            || health_query.get(target).map_or(true, |health| health.is_dead())
+        {
+            continue;
+        }
 
-    if let Ok((enemy_entity, mut enemy)) = enemy_query.get_mut(*enemy) {
-        enemy.take_damage(10);
-        commands.entity(*bullet).despawn();
+        damage_events.send(DamageEvent {
+            target,
+            amount: bullet.damage,
+            source: Some(bullet.owner),
+            kind: DamageKind::Bullet,
+            critical: bullet.critical,
+        });
 
-        if enemy.health.current == 0 {
-            commands.entity(enemy_entity).despawn();
+        if bullet.pierce > 0 {
+            bullet.pierce -= 1;
+        } else {
+            spent.push(bullet_entity);
         }
-        true
-    } else {
-        false
+    }
+
+    for bullet_entity in spent {
+        commands.entity(bullet_entity).despawn();
     }
 }
 
-fn process_bullet_wall_collision(
-    bullet_entity: &Entity,
-    entity1: &Entity,
-    entity2: &Entity,
-    commands: &mut Commands,
-    wall_query: &Query<Entity, With<Wall>>,
-) -> bool {
-    if wall_query.get(*entity1).is_ok() || wall_query.get(*entity2).is_ok() {
-        commands.entity(*bullet_entity).despawn();
-        true
-    } else {
-        false
+/// Entity taking the hit for a collider: the collider itself or, for child colliders, its parent.
+fn hit_target(
+    collider: Entity,
+    health_query: &Query<&Health>,
+    parent_query: &Query<&Parent>,
+) -> Option<Entity> {
+    if health_query.contains(collider) {
+        return Some(collider);
     }
+
+    let parent = parent_query.get(collider).ok()?.get();
+    health_query.contains(parent).then_some(parent)
 }
 
 fn destroy_expired_bullets(
@@ -135,9 +141,3 @@ fn destroy_expired_bullets(
         }
     }
 }
-
-fn move_bullets(time: Res<Time>, mut query: Query<(&mut Transform, &Bullet)>) {
-    for (mut transform, bullet) in query.iter_mut() {
-        transform.translation += bullet.velocity.extend(0.0) * time.delta_seconds();
-    }
-}
@@ -0,0 +1,110 @@
+use bevy::app::{App, Update};
+use bevy::math::Vec2;
+use bevy::prelude::{
+    in_state, Entity, Event, EventReader, EventWriter, IntoSystemConfigs, Plugin, Query, Res, Time,
+    Transform,
+};
+
+use crate::components::damage::{DamageKind, Resistances};
+use crate::components::health::Health;
+use crate::components::invulnerability::Invulnerability;
+use crate::resources::app_state::AppState;
+
+/// Request to hurt `target`; the amount is reduced by the target's `Resistances` before it is applied.
+#[derive(Event)]
+pub struct DamageEvent {
+    pub(crate) target: Entity,
+    pub(crate) amount: i32,
+    /// Entity credited with the hit, such as the shooter of a bullet.
+    pub(crate) source: Option<Entity>,
+    pub(crate) kind: DamageKind,
+    pub(crate) critical: bool,
+}
+
+/// Sent for every hit that actually lowered health, for feedback such as damage numbers.
+#[derive(Event)]
+pub struct DamageDealt {
+    pub(crate) target: Entity,
+    pub(crate) position: Vec2,
+    pub(crate) amount: i32,
+    pub(crate) critical: bool,
+}
+
+/// Sent once when any entity's health drops to zero.
+#[derive(Event)]
+pub struct Died {
+    pub(crate) entity: Entity,
+    pub(crate) position: Vec2,
+    pub(crate) killer: Option<Entity>,
+}
+
+pub struct DamagePlugin;
+
+impl Plugin for DamagePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<DamageEvent>()
+            .add_event::<DamageDealt>()
+            .add_event::<Died>()
+            .add_systems(
+                Update,
+                (tick_invulnerability, apply_damage)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            )
+        ;
+    }
+}
+
+fn tick_invulnerability(time: Res<Time>, mut invulnerability_query: Query<&mut Invulnerability>) {
+    for mut invulnerability in invulnerability_query.iter_mut() {
+        invulnerability.0.tick(time.delta());
+    }
+}
+
+fn apply_damage(
+    mut damage_events: EventReader<DamageEvent>,
+    mut target_query: Query<(&mut Health, &Transform, Option<&Resistances>, Option<&mut Invulnerability>)>,
+    mut damage_dealt_events: EventWriter<DamageDealt>,
+    mut died_events: EventWriter<Died>,
+) {
+    for event in damage_events.read() {
+        let Ok((mut health, transform, resistances, invulnerability)) =
+            target_query.get_mut(event.target)
+        else {
+            continue;
+        };
+        let invulnerable = invulnerability.as_ref().map_or(false, |invulnerability| invulnerability.is_active());
+        if health.is_dead() || invulnerable {
+            continue;
+        }
+
+        let amount = resistances.map_or(event.amount, |resistances| {
+            resistances.apply(event.amount, event.kind)
+        });
+        if amount <= 0 {
+            continue;
+        }
+
+        health.take_damage(amount);
+        if let Some(mut invulnerability) = invulnerability {
+            invulnerability.0.reset();
+        }
+
+        let position = transform.translation.truncate();
+        damage_dealt_events.send(DamageDealt {
+            target: event.target,
+            position,
+            amount,
+            critical: event.critical,
+        });
+
+        if health.is_dead() {
+            died_events.send(Died {
+                entity: event.target,
+                position,
+                killer: event.source,
+            });
+        }
+    }
+}
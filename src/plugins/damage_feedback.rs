@@ -0,0 +1,174 @@
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+
+use crate::components::enemy::Enemy;
+use crate::components::health::Health;
+use crate::components::player::Player;
+use crate::plugins::damage::DamageDealt;
+use crate::resources::app_state::AppState;
+use crate::resources::constants::{
+    DAMAGE_NUMBER_RISE_SPEED, DAMAGE_NUMBER_SECONDS, ENEMY_HEALTH_BAR_SIZE,
+};
+use crate::systems::cleanup::despawn_with;
+
+/// Gap between the top of an enemy sprite and its health bar.
+const HEALTH_BAR_MARGIN: f32 = 8.;
+
+/// Background of the health bar above an enemy, added the first time the enemy gets hurt.
+#[derive(Component)]
+pub struct EnemyHealthBar;
+
+#[derive(Component)]
+pub struct EnemyHealthBarFill;
+
+#[derive(Component)]
+pub struct DamageNumber {
+    timer: Timer,
+}
+
+pub struct DamageFeedbackPlugin;
+
+impl Plugin for DamageFeedbackPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(AppState::MainMenu), despawn_with::<DamageNumber>)
+            .add_systems(
+                Update,
+                (
+                    spawn_damage_numbers,
+                    attach_enemy_health_bars,
+                    update_enemy_health_bars,
+                    float_damage_numbers,
+                )
+                    .run_if(in_state(AppState::Playing)),
+            )
+        ;
+    }
+}
+
+fn spawn_damage_numbers(
+    mut commands: Commands,
+    mut damage_dealt_events: EventReader<DamageDealt>,
+    player_query: Query<(), With<Player>>,
+) {
+    for event in damage_dealt_events.read() {
+        let (color, font_size, text) = if player_query.contains(event.target) {
+            (Color::rgb(1., 0.2, 0.2), 22., format!("-{}", event.amount))
+        } else if event.critical {
+            (Color::rgb(1., 0.75, 0.), 30., format!("{}!", event.amount))
+        } else {
+            (Color::WHITE, 22., event.amount.to_string())
+        };
+
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    text,
+                    TextStyle {
+                        font_size,
+                        color,
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_translation(event.position.extend(20.)),
+                ..default()
+            },
+            DamageNumber {
+                timer: Timer::from_seconds(DAMAGE_NUMBER_SECONDS, TimerMode::Once),
+            },
+        ));
+    }
+}
+
+fn float_damage_numbers(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut number_query: Query<(Entity, &mut DamageNumber, &mut Transform, &mut Text)>,
+) {
+    for (entity, mut number, mut transform, mut text) in number_query.iter_mut() {
+        if number.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        transform.translation.y += DAMAGE_NUMBER_RISE_SPEED * time.delta_seconds();
+        text.sections[0].style.color.set_a(number.timer.percent_left());
+    }
+}
+
+fn attach_enemy_health_bars(
+    mut commands: Commands,
+    mut damage_dealt_events: EventReader<DamageDealt>,
+    enemy_query: Query<(Option<&Children>, &TextureAtlasSprite), With<Enemy>>,
+    bar_query: Query<(), With<EnemyHealthBar>>,
+) {
+    // Children only show up once commands are applied, so hits within one frame are tracked here.
+    let mut attached = Vec::new();
+
+    for event in damage_dealt_events.read() {
+        let Ok((children, sprite)) = enemy_query.get(event.target) else {
+            continue;
+        };
+        if attached.contains(&event.target)
+            || children.map_or(false, |children| children.iter().any(|child| bar_query.contains(*child)))
+        {
+            continue;
+        }
+        attached.push(event.target);
+
+        let sprite_height = sprite.custom_size.map_or(0., |size| size.y);
+        let bar = commands
+            .spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgba(0., 0., 0., 0.6),
+                        custom_size: Some(ENEMY_HEALTH_BAR_SIZE),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(0., sprite_height / 2. + HEALTH_BAR_MARGIN, 1.),
+                    ..default()
+                },
+                EnemyHealthBar,
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::rgb(0.8, 0.1, 0.1),
+                            custom_size: Some(ENEMY_HEALTH_BAR_SIZE),
+                            anchor: Anchor::CenterLeft,
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(-ENEMY_HEALTH_BAR_SIZE.x / 2., 0., 0.1),
+                        ..default()
+                    },
+                    EnemyHealthBarFill,
+                ));
+            })
+            .id();
+
+        commands.entity(event.target).add_child(bar);
+    }
+}
+
+fn update_enemy_health_bars(
+    enemy_query: Query<&Health, With<Enemy>>,
+    bar_query: Query<&Parent, With<EnemyHealthBar>>,
+    mut fill_query: Query<(&Parent, &mut Sprite), With<EnemyHealthBarFill>>,
+) {
+    for (bar, mut sprite) in fill_query.iter_mut() {
+        let Some(health) = bar_query
+            .get(bar.get())
+            .ok()
+            .and_then(|enemy| enemy_query.get(enemy.get()).ok())
+        else {
+            continue;
+        };
+        let width = ENEMY_HEALTH_BAR_SIZE.x * health.fraction();
+        let size = Some(Vec2::new(width, ENEMY_HEALTH_BAR_SIZE.y));
+
+        if sprite.custom_size != size {
+            sprite.custom_size = size;
+        }
+    }
+}
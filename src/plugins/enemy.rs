@@ -1,93 +1,300 @@
+use benimator::FrameRate;
 use bevy::app::App;
-use bevy::math::{Quat, Vec3};
-use bevy::prelude::{Commands, Component, ParamSet, Plugin, Query, Res, Startup, Time, Timer, TimerMode, Transform, TransformBundle, Update, With};
-use bevy_rapier2d::dynamics::RigidBody;
+use bevy::math::Vec2;
+use bevy::prelude::{
+    in_state, Asset, AssetApp, AssetServer, Assets, Color, Commands, Component,
+    DespawnRecursiveExt, Entity, Event, EventReader, EventWriter, Handle, IntoSystemConfigs,
+    OnEnter, OnExit, Plugin, Quat, Query, Res, ResMut, Resource, SpriteSheetBundle, Startup,
+    TextureAtlas, TextureAtlasSprite, Time, Transform, Update, With,
+};
+use bevy::reflect::TypePath;
+use bevy::utils::HashMap;
+use bevy_rapier2d::dynamics::{LockedAxes, RigidBody, Velocity};
 use bevy_rapier2d::geometry::{ActiveEvents, Collider, ColliderMassProperties};
-use rand::{random, Rng, thread_rng};
+use serde::Deserialize;
 
+use crate::components::corpse::Corpse;
+use crate::components::damage::Resistances;
 use crate::components::enemy::Enemy;
-use crate::components::player::Player;
+use crate::components::enemy_ai::{AiParams, EnemyAi};
+use crate::components::enemy_attack::{AttackStats, EnemyAttack};
+use crate::components::faction::Faction;
+use crate::components::health::Health;
+use crate::components::path_follower::PathFollower;
+use crate::components::perception::Perception;
+use crate::helpers::json_asset::JsonAssetLoader;
+use crate::plugins::damage::Died;
+use crate::plugins::game_state::LoadingAssets;
+use crate::plugins::pickup::LootDrop;
+use crate::plugins::player::{Animation, AnimationState};
+use crate::resources::app_state::AppState;
+use crate::resources::constants::{
+    CORPSE_FADE_SECONDS, CORPSE_FALL_SECONDS, CORPSE_SECONDS, ENEMY_REPATH_SECONDS,
+};
+use crate::systems::cleanup::despawn_with;
 
-#[derive(Component)]
-pub struct EnemyTimer(pub(crate) Timer);
+/// Layout and animations of an enemy sprite sheet; frame ranges are inclusive atlas indices.
+#[derive(Deserialize, Clone)]
+pub struct EnemySpriteSheet {
+    pub(crate) path: String,
+    pub(crate) tile_size: [f32; 2],
+    pub(crate) columns: usize,
+    pub(crate) rows: usize,
+    pub(crate) idle: (usize, usize),
+    pub(crate) running: (usize, usize),
+    pub(crate) fps: f64,
+    pub(crate) scale: f32,
+    #[serde(default = "white")]
+    pub(crate) tint: [f32; 3],
+}
+
+fn white() -> [f32; 3] {
+    [1., 1., 1.]
+}
+
+/// Everything needed to spawn one kind of enemy.
+#[derive(Deserialize, Clone)]
+pub struct EnemyArchetype {
+    pub(crate) health: i32,
+    pub(crate) speed: f32,
+    pub(crate) radius: f32,
+    pub(crate) mass: f32,
+    pub(crate) sprite: EnemySpriteSheet,
+    pub(crate) attack: AttackStats,
+    pub(crate) ai: AiParams,
+    #[serde(default)]
+    pub(crate) resistances: Resistances,
+    /// Points awarded to the player for the kill.
+    #[serde(default)]
+    pub(crate) score: u32,
+    #[serde(default)]
+    pub(crate) loot: Vec<LootDrop>,
+}
+
+#[derive(Asset, TypePath, Deserialize)]
+pub struct EnemyArchetypes(pub(crate) HashMap<String, EnemyArchetype>);
+
+#[derive(Resource)]
+pub struct EnemyArchetypesHandle(pub(crate) Handle<EnemyArchetypes>);
+
+/// Texture atlases built from the archetype sprite sheets, by archetype name.
+#[derive(Resource, Default)]
+pub struct EnemyAtlases(pub(crate) HashMap<String, Handle<TextureAtlas>>);
+
+/// Sent once when an enemy's health drops to zero; the enemy is replaced by a corpse right after.
+#[derive(Event)]
+pub struct EnemyDied {
+    pub(crate) entity: Entity,
+    pub(crate) position: Vec2,
+    pub(crate) archetype: String,
+    pub(crate) killer: Option<Entity>,
+}
+
+#[derive(Component, Clone)]
+pub struct EnemyAnimations {
+    idle: Animation,
+    running: Animation,
+}
 
 pub struct EnemyPlugin;
 
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_systems(Startup, spawn_enemy)
-            .add_systems(Update, move_enemies)
-            .add_systems(Update, chase_player)
+            .init_asset::<EnemyArchetypes>()
+            .register_asset_loader(JsonAssetLoader::<EnemyArchetypes>::new(&["enemies.json"]))
+            .init_resource::<EnemyAtlases>()
+            .add_event::<EnemyDied>()
+            .add_systems(Startup, load_enemy_archetypes)
+            .add_systems(OnExit(AppState::Loading), build_enemy_atlases)
+            .add_systems(OnEnter(AppState::MainMenu), (despawn_with::<Enemy>, despawn_with::<Corpse>))
+            .add_systems(
+                Update,
+                (
+                    animate_enemies,
+                    (announce_enemy_deaths, replace_dead_enemies_with_corpses).chain(),
+                    animate_corpses,
+                )
+                    .run_if(in_state(AppState::Playing)),
+            )
         ;
     }
 }
 
-fn spawn_enemy(
-    mut commands: Commands
+fn load_enemy_archetypes(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading_assets: ResMut<LoadingAssets>,
 ) {
-    let mut rng = thread_rng();
+    let archetypes = asset_server.load("archetypes.enemies.json");
+    loading_assets.0.push(archetypes.clone().untyped());
+    commands.insert_resource(EnemyArchetypesHandle(archetypes));
+}
 
-    for _ in 0..10 {
-        let x = rng.gen_range(-200.0..250.0);
-        let y = rng.gen_range(-200.0..250.0);
+fn build_enemy_atlases(
+    asset_server: Res<AssetServer>,
+    archetypes_handle: Res<EnemyArchetypesHandle>,
+    archetypes: Res<Assets<EnemyArchetypes>>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut enemy_atlases: ResMut<EnemyAtlases>,
+) {
+    let Some(archetypes) = archetypes.get(&archetypes_handle.0) else {
+        return;
+    };
 
-        commands
-            .spawn((
-                RigidBody::Dynamic,
-                Collider::ball(25.),
-                ColliderMassProperties::Mass(100.),
-                TransformBundle::from(Transform::from_xyz(x, y, 0.)),
-                ActiveEvents::COLLISION_EVENTS
-            ))
-            .insert(Enemy::new(100, 100.0))
-            .insert(EnemyTimer(Timer::from_seconds(1.0, TimerMode::Once)))
-        ;
+    enemy_atlases.0.clear();
+    for (name, archetype) in archetypes.0.iter() {
+        let sheet = &archetype.sprite;
+        let texture_atlas = TextureAtlas::from_grid(
+            asset_server.load(&sheet.path),
+            Vec2::from(sheet.tile_size),
+            sheet.columns,
+            sheet.rows,
+            None,
+            None,
+        );
+
+        enemy_atlases.0.insert(name.clone(), texture_atlases.add(texture_atlas));
     }
 }
 
-fn chase_player(
-    time: Res<Time>,
-    mut query_set: ParamSet<(
-        Query<&Transform, With<Player>>,
-        Query<(&mut Enemy, &mut Transform)>
-    )>,
+pub fn spawn_enemy(
+    commands: &mut Commands,
+    name: &str,
+    archetype: &EnemyArchetype,
+    enemy_atlases: &EnemyAtlases,
+    position: Vec2,
+) -> Entity {
+    let sheet = &archetype.sprite;
+    let animations = EnemyAnimations {
+        idle: Animation(benimator::Animation::from_indices(
+            sheet.idle.0..=sheet.idle.1,
+            FrameRate::from_fps(sheet.fps),
+        )),
+        running: Animation(benimator::Animation::from_indices(
+            sheet.running.0..=sheet.running.1,
+            FrameRate::from_fps(sheet.fps),
+        )),
+    };
+    let [r, g, b] = sheet.tint;
+
+    commands
+        .spawn((
+            RigidBody::Dynamic,
+            Velocity::zero(),
+            LockedAxes::ROTATION_LOCKED,
+            Collider::ball(archetype.radius),
+            ColliderMassProperties::Mass(archetype.mass),
+            ActiveEvents::COLLISION_EVENTS
+        ))
+        .insert(SpriteSheetBundle {
+            texture_atlas: enemy_atlases.0.get(name).cloned().unwrap_or_default(),
+            // Sized rather than scaled, as Rapier would scale the collider along with the transform.
+            sprite: TextureAtlasSprite {
+                color: Color::rgb(r, g, b),
+                custom_size: Some(Vec2::from(sheet.tile_size) * sheet.scale),
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(position.x, position.y, 5.),
+            ..Default::default()
+        })
+        .insert(animations.idle.clone())
+        .insert(animations)
+        .insert(AnimationState::default())
+        .insert(Enemy::new(name.to_string(), archetype.speed))
+        .insert(Health::new(archetype.health))
+        .insert(archetype.resistances.clone())
+        .insert(Faction::Enemy)
+        .insert(EnemyAttack::new(archetype.attack))
+        .insert(EnemyAi::new(position, archetype.ai))
+        .insert(PathFollower::new(ENEMY_REPATH_SECONDS))
+        .insert(Perception::new(archetype.ai.view_distance, archetype.ai.field_of_view, archetype.ai.hearing_radius))
+        .id()
+}
+
+/// Plays the running animation while an enemy moves, mirrored when it heads left.
+fn animate_enemies(
+    mut enemy_query: Query<(&Velocity, &EnemyAnimations, &mut Animation, &mut TextureAtlasSprite), With<Enemy>>,
 ) {
-    let player_position = query_set.p0().get_single().map(|t| t.translation);
-
-    if let Ok(player_translation) = player_position {
-        for (mut enemy, mut enemy_transform) in query_set.p1().iter_mut() {
-            let direction_to_player = player_translation - enemy_transform.translation;
-            let distance_to_player = direction_to_player.length();
-
-            if distance_to_player < 250.0 {
-                enemy.player_spotted = true;
-                let direction_normalized = direction_to_player.normalize_or_zero();
-                enemy_transform.translation += direction_normalized * time.delta_seconds() * enemy.speed;
-            } else {
-                enemy.player_spotted = false;
-            }
+    for (velocity, animations, mut animation, mut sprite) in enemy_query.iter_mut() {
+        let moving = velocity.linvel.length_squared() > 1.;
+        let new_animation = if moving { &animations.running } else { &animations.idle };
+
+        if animation.0 != new_animation.0 {
+            *animation = new_animation.clone();
         }
+        if moving {
+            sprite.flip_x = velocity.linvel.x < 0.;
+        }
+    }
+}
+
+fn announce_enemy_deaths(
+    mut died_events: EventReader<Died>,
+    enemy_query: Query<&Enemy>,
+    mut enemy_died_events: EventWriter<EnemyDied>,
+) {
+    for event in died_events.read() {
+        let Ok(enemy) = enemy_query.get(event.entity) else {
+            continue;
+        };
+
+        enemy_died_events.send(EnemyDied {
+            entity: event.entity,
+            position: event.position,
+            archetype: enemy.archetype.clone(),
+            killer: event.killer,
+        });
     }
 }
 
-fn move_enemies(
+fn replace_dead_enemies_with_corpses(
+    mut commands: Commands,
+    mut enemy_died_events: EventReader<EnemyDied>,
+    enemy_query: Query<(&Transform, &Handle<TextureAtlas>, &TextureAtlasSprite, &Velocity), With<Enemy>>,
+) {
+    for event in enemy_died_events.read() {
+        let Ok((transform, texture_atlas, sprite, velocity)) = enemy_query.get(event.entity) else {
+            continue;
+        };
+
+        // Fall over away from the direction the enemy was running in.
+        let fall_angle = if velocity.linvel.x < 0. {
+            -std::f32::consts::FRAC_PI_2
+        } else {
+            std::f32::consts::FRAC_PI_2
+        };
+
+        commands
+            .spawn(SpriteSheetBundle {
+                texture_atlas: texture_atlas.clone(),
+                sprite: sprite.clone(),
+                transform: Transform::from_translation(transform.translation.truncate().extend(4.)),
+                ..Default::default()
+            })
+            .insert(Corpse::new(CORPSE_SECONDS, fall_angle))
+        ;
+        commands.entity(event.entity).despawn_recursive();
+    }
+}
+
+fn animate_corpses(
+    mut commands: Commands,
     time: Res<Time>,
-    mut query: Query<(&mut Transform, &Enemy, &mut EnemyTimer)>,
+    mut corpse_query: Query<(Entity, &mut Corpse, &mut Transform, &mut TextureAtlasSprite)>,
 ) {
-    for (mut transform, enemy, mut timer) in query.iter_mut() {
-        if enemy.player_spotted == false {
-            return;
+    for (entity, mut corpse, mut transform, mut sprite) in corpse_query.iter_mut() {
+        corpse.timer.tick(time.delta());
+        if corpse.timer.finished() {
+            commands.entity(entity).despawn();
+            continue;
         }
 
-        timer.0.tick(time.delta());
-        if timer.0.finished() {
-            let random_angle = random::<f32>() * 2.0 * std::f32::consts::PI;
-            transform.rotation = Quat::from_rotation_z(random_angle);
-            timer.0.reset();
-        }
+        let elapsed = corpse.timer.elapsed_secs();
+        let remaining = corpse.timer.duration().as_secs_f32() - elapsed;
 
-        let forward = transform.rotation * Vec3::Y;
-        transform.translation += forward * enemy.speed * time.delta_seconds();
+        let fallen = (elapsed / CORPSE_FALL_SECONDS).min(1.);
+        transform.rotation = Quat::from_rotation_z(corpse.fall_angle * fallen);
+        sprite.color.set_a((remaining / CORPSE_FADE_SECONDS).min(1.));
     }
 }
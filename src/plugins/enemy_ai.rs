@@ -0,0 +1,605 @@
+use bevy::app::App;
+use bevy::math::Vec2;
+use bevy::prelude::{
+    in_state, Commands, Entity, EventReader, EventWriter, IntoSystemConfigs, Plugin, Query, Res,
+    Time, Timer, TimerMode, Transform, Update, With,
+};
+use bevy_rapier2d::dynamics::Velocity;
+use bevy_rapier2d::pipeline::QueryFilter;
+use bevy_rapier2d::plugin::RapierContext;
+use rand::{thread_rng, Rng};
+
+use crate::components::bullet::Bullet;
+use crate::components::damage::DamageKind;
+use crate::components::enemy::Enemy;
+use crate::components::enemy_ai::{EnemyAi, EnemyState};
+use crate::components::enemy_attack::EnemyAttack;
+use crate::components::faction::Faction;
+use crate::components::health::Health;
+use crate::components::invulnerability::Invulnerability;
+use crate::components::path_follower::PathFollower;
+use crate::components::perception::Perception;
+use crate::components::player::Player;
+use crate::components::wall::Wall;
+use crate::helpers::aim::lead_target;
+use crate::helpers::navigation::NavGrid;
+use crate::plugins::bullet::spawn_bullet;
+use crate::plugins::damage::DamageEvent;
+use crate::plugins::weapon::Gunshot;
+use crate::resources::app_state::AppState;
+use crate::resources::constants::{
+    ENEMY_FLEE_DISTANCE, ENEMY_SEARCH_TURN_SPEED, MAX_PATHS_PER_FRAME, WAYPOINT_REACH,
+};
+
+/// Attacking enemies only fall back to chasing once the player is this much further than their reach.
+const ATTACK_RANGE_HYSTERESIS: f32 = 1.25;
+
+pub struct EnemyAiPlugin;
+
+impl Plugin for EnemyAiPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(
+                Update,
+                (
+                    perceive_player,
+                    hear_gunshots,
+                    flee_at_low_health,
+                    idle,
+                    patrol,
+                    chase,
+                    attack,
+                    search,
+                    flee,
+                    plan_enemy_paths,
+                    follow_path,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            )
+        ;
+    }
+}
+
+/// Moves the enemy to `state`, dropping the route planned for the previous one.
+fn transition(ai: &mut EnemyAi, path_follower: &mut PathFollower, state: EnemyState) {
+    if ai.transition(state) {
+        path_follower.clear();
+    }
+}
+
+fn perceive_player(
+    rapier_context: Res<RapierContext>,
+    player_query: Query<&Transform, With<Player>>,
+    wall_query: Query<(), With<Wall>>,
+    mut enemy_query: Query<(&mut Enemy, &Perception, &Transform)>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let player_position = player_transform.translation.truncate();
+    let is_wall = |entity| wall_query.contains(entity);
+
+    for (mut enemy, perception, enemy_transform) in enemy_query.iter_mut() {
+        let enemy_position = enemy_transform.translation.truncate();
+        let to_player = player_position - enemy_position;
+        let distance = to_player.length();
+        let direction = to_player.normalize_or_zero();
+
+        // Once alerted an enemy keeps tracking the player outside of its vision cone.
+        let in_view = distance <= perception.view_distance
+            && (enemy.player_spotted
+                || enemy.facing.angle_between(direction).abs() <= perception.field_of_view / 2.);
+
+        let visible = in_view
+            && rapier_context
+                .cast_ray(enemy_position, direction, distance, true, QueryFilter::new().predicate(&is_wall))
+                .is_none();
+
+        enemy.player_spotted = visible;
+        if visible {
+            enemy.last_known_player_position = Some(player_position);
+        }
+    }
+}
+
+fn hear_gunshots(
+    mut gunshot_events: EventReader<Gunshot>,
+    mut enemy_query: Query<(&mut Enemy, &Perception, &Transform)>,
+) {
+    for gunshot in gunshot_events.read() {
+        for (mut enemy, perception, enemy_transform) in enemy_query.iter_mut() {
+            let enemy_position = enemy_transform.translation.truncate();
+
+            if !enemy.player_spotted
+                && enemy_position.distance(gunshot.position) <= perception.hearing_radius
+            {
+                enemy.last_known_player_position = Some(gunshot.position);
+                enemy.facing = (gunshot.position - enemy_position)
+                    .try_normalize()
+                    .unwrap_or(enemy.facing);
+            }
+        }
+    }
+}
+
+fn flee_at_low_health(mut enemy_query: Query<(&Health, &mut EnemyAi, &mut PathFollower), With<Enemy>>) {
+    for (health, mut ai, mut path_follower) in enemy_query.iter_mut() {
+        if health.fraction() <= ai.params.flee_health_fraction {
+            transition(&mut ai, &mut path_follower, EnemyState::Flee);
+        }
+    }
+}
+
+fn idle(time: Res<Time>, mut enemy_query: Query<(&Enemy, &mut EnemyAi, &mut PathFollower)>) {
+    for (enemy, mut ai, mut path_follower) in enemy_query.iter_mut() {
+        if ai.state != EnemyState::Idle {
+            continue;
+        }
+
+        path_follower.target = None;
+
+        if enemy.player_spotted {
+            transition(&mut ai, &mut path_follower, EnemyState::Chase);
+        } else if enemy.last_known_player_position.is_some() {
+            transition(&mut ai, &mut path_follower, EnemyState::Search);
+        } else if ai.state_timer.tick(time.delta()).finished() {
+            transition(&mut ai, &mut path_follower, EnemyState::Patrol);
+        }
+    }
+}
+
+fn patrol(
+    time: Res<Time>,
+    mut enemy_query: Query<(&Enemy, &Transform, &mut EnemyAi, &mut PathFollower)>,
+) {
+    let mut rng = thread_rng();
+
+    for (enemy, enemy_transform, mut ai, mut path_follower) in enemy_query.iter_mut() {
+        if ai.state != EnemyState::Patrol {
+            continue;
+        }
+
+        if enemy.player_spotted {
+            transition(&mut ai, &mut path_follower, EnemyState::Chase);
+            continue;
+        }
+        if enemy.last_known_player_position.is_some() {
+            transition(&mut ai, &mut path_follower, EnemyState::Search);
+            continue;
+        }
+
+        let home = ai.home;
+        let patrol_radius = ai.params.patrol_radius;
+        let patrol_target = *ai.patrol_target.get_or_insert_with(|| {
+            home + Vec2::from_angle(rng.gen_range(0.0..std::f32::consts::TAU))
+                * rng.gen_range(0.0..=patrol_radius)
+        });
+        path_follower.target = Some(patrol_target);
+
+        let arrived = enemy_transform.translation.truncate().distance(patrol_target) <= WAYPOINT_REACH;
+
+        // Give up on patrol points that turn out to be unreachable.
+        if arrived || ai.state_timer.tick(time.delta()).finished() {
+            transition(&mut ai, &mut path_follower, EnemyState::Idle);
+        }
+    }
+}
+
+fn chase(
+    player_query: Query<&Transform, With<Player>>,
+    mut enemy_query: Query<(&Enemy, &EnemyAttack, &Transform, &mut EnemyAi, &mut PathFollower)>,
+) {
+    for (enemy, enemy_attack, enemy_transform, mut ai, mut path_follower) in enemy_query.iter_mut() {
+        if ai.state != EnemyState::Chase {
+            continue;
+        }
+
+        if !enemy.player_spotted {
+            transition(&mut ai, &mut path_follower, EnemyState::Search);
+            continue;
+        }
+        let Ok(player_transform) = player_query.get_single() else {
+            transition(&mut ai, &mut path_follower, EnemyState::Idle);
+            continue;
+        };
+        let player_position = player_transform.translation.truncate();
+
+        if enemy_transform.translation.truncate().distance(player_position) <= enemy_attack.stats.range {
+            transition(&mut ai, &mut path_follower, EnemyState::Attack);
+        } else {
+            path_follower.target = Some(player_position);
+        }
+    }
+}
+
+fn attack(
+    mut commands: Commands,
+    time: Res<Time>,
+    player_query: Query<(Entity, &Player, &Transform, &Health, &Invulnerability)>,
+    mut enemy_query: Query<(Entity, &mut Enemy, &mut EnemyAttack, &Transform, &mut EnemyAi, &mut PathFollower)>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for (enemy_entity, mut enemy, mut enemy_attack, enemy_transform, mut ai, mut path_follower) in
+        enemy_query.iter_mut()
+    {
+        if ai.state != EnemyState::Attack {
+            continue;
+        }
+
+        enemy_attack.cooldown.tick(time.delta());
+        path_follower.target = None;
+
+        let Ok((player_entity, player, player_transform, player_health, invulnerability)) =
+            player_query.get_single()
+        else {
+            transition(&mut ai, &mut path_follower, EnemyState::Idle);
+            continue;
+        };
+        let enemy_position = enemy_transform.translation.truncate();
+        let player_position = player_transform.translation.truncate();
+        let to_player = player_position - enemy_position;
+
+        if !enemy.player_spotted {
+            transition(&mut ai, &mut path_follower, EnemyState::Search);
+            continue;
+        }
+        if to_player.length() > enemy_attack.stats.range * ATTACK_RANGE_HYSTERESIS {
+            transition(&mut ai, &mut path_follower, EnemyState::Chase);
+            continue;
+        }
+
+        enemy.facing = to_player.try_normalize().unwrap_or(enemy.facing);
+
+        if !enemy_attack.cooldown.finished() || player_health.is_dead() {
+            continue;
+        }
+
+        let stats = enemy_attack.stats;
+        if let Some(projectile) = stats.projectile {
+            enemy_attack.cooldown.reset();
+
+            let aim_point = lead_target(enemy_position, player_position, player.velocity, projectile.speed);
+            spawn_bullet(
+                &mut commands,
+                enemy_position,
+                (aim_point - enemy_position).normalize_or_zero() * projectile.speed,
+                Bullet {
+                    owner: enemy_entity,
+                    faction: Faction::Enemy,
+                    lifetime: Timer::from_seconds(projectile.lifetime, TimerMode::Once),
+                    damage: stats.damage,
+                    critical: false,
+                    pierce: 0,
+                },
+            );
+            continue;
+        }
+
+        if invulnerability.is_active() {
+            continue;
+        }
+
+        enemy_attack.cooldown.reset();
+        damage_events.send(DamageEvent {
+            target: player_entity,
+            amount: stats.damage,
+            source: Some(enemy_entity),
+            kind: DamageKind::Melee,
+            critical: false,
+        });
+    }
+}
+
+fn search(
+    time: Res<Time>,
+    mut enemy_query: Query<(&mut Enemy, &Transform, &mut EnemyAi, &mut PathFollower)>,
+) {
+    for (mut enemy, enemy_transform, mut ai, mut path_follower) in enemy_query.iter_mut() {
+        if ai.state != EnemyState::Search {
+            continue;
+        }
+
+        if enemy.player_spotted {
+            transition(&mut ai, &mut path_follower, EnemyState::Chase);
+            continue;
+        }
+
+        if let Some(last_known_position) = enemy.last_known_player_position {
+            if enemy_transform.translation.truncate().distance(last_known_position) > WAYPOINT_REACH {
+                path_follower.target = Some(last_known_position);
+                continue;
+            }
+
+            // Reached the spot the player was last seen at without finding them.
+            enemy.last_known_player_position = None;
+            path_follower.target = None;
+        }
+
+        // Look around before giving up.
+        enemy.facing = Vec2::from_angle(ENEMY_SEARCH_TURN_SPEED * time.delta_seconds()).rotate(enemy.facing);
+
+        if ai.state_timer.tick(time.delta()).finished() {
+            transition(&mut ai, &mut path_follower, EnemyState::Patrol);
+        }
+    }
+}
+
+fn flee(
+    player_query: Query<&Transform, With<Player>>,
+    mut enemy_query: Query<(&Perception, &Transform, &Health, &mut EnemyAi, &mut PathFollower)>,
+) {
+    let player_position = player_query
+        .get_single()
+        .ok()
+        .map(|player_transform| player_transform.translation.truncate());
+
+    for (perception, enemy_transform, health, mut ai, mut path_follower) in enemy_query.iter_mut() {
+        if ai.state != EnemyState::Flee {
+            continue;
+        }
+
+        // Enemies do not regenerate, but one healed back above the threshold returns to the fight.
+        if health.fraction() > ai.params.flee_health_fraction {
+            transition(&mut ai, &mut path_follower, EnemyState::Idle);
+            continue;
+        }
+
+        let enemy_position = enemy_transform.translation.truncate();
+
+        // Cower in place once the player is far enough away.
+        path_follower.target = player_position
+            .filter(|player_position| player_position.distance(enemy_position) <= perception.view_distance)
+            .map(|player_position| {
+                enemy_position + (enemy_position - player_position).normalize_or_zero() * ENEMY_FLEE_DISTANCE
+            });
+    }
+}
+
+fn plan_enemy_paths(
+    time: Res<Time>,
+    nav_grid: Option<Res<NavGrid>>,
+    mut enemy_query: Query<(&Transform, &mut PathFollower), With<Enemy>>,
+) {
+    let Some(nav_grid) = nav_grid else {
+        return;
+    };
+    let mut budget = MAX_PATHS_PER_FRAME;
+
+    for (enemy_transform, mut path_follower) in enemy_query.iter_mut() {
+        path_follower.repath.tick(time.delta());
+
+        let Some(target) = path_follower.target else {
+            path_follower.waypoints.clear();
+            continue;
+        };
+        if budget == 0 || !path_follower.repath.finished() {
+            continue;
+        }
+
+        budget -= 1;
+        path_follower.repath.reset();
+        path_follower.waypoints = nav_grid
+            .find_path(enemy_transform.translation.truncate(), target)
+            .unwrap_or_default()
+            .into();
+    }
+}
+
+fn follow_path(mut enemy_query: Query<(&mut Enemy, &Transform, &mut Velocity, &mut PathFollower)>) {
+    for (mut enemy, enemy_transform, mut velocity, mut path_follower) in enemy_query.iter_mut() {
+        let Some(target) = path_follower.target else {
+            velocity.linvel = Vec2::ZERO;
+            continue;
+        };
+        let enemy_position = enemy_transform.translation.truncate();
+
+        if enemy_position.distance(target) <= WAYPOINT_REACH {
+            velocity.linvel = Vec2::ZERO;
+            continue;
+        }
+
+        // Head straight for the target until the first path has been planned.
+        let direction = path_follower
+            .next_waypoint(enemy_position, WAYPOINT_REACH)
+            .map_or(target - enemy_position, |waypoint| waypoint - enemy_position);
+        velocity.linvel = direction.normalize_or_zero() * enemy.speed;
+
+        if velocity.linvel != Vec2::ZERO {
+            enemy.facing = velocity.linvel.normalize();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+    use bevy_rapier2d::prelude::{
+        Collider, LockedAxes, NoUserData, RapierConfiguration, RapierPhysicsPlugin, RigidBody, TimestepMode,
+    };
+
+    use super::*;
+    use crate::components::enemy_ai::AiParams;
+    use crate::components::enemy_attack::AttackStats;
+    use crate::resources::constants::ENEMY_REPATH_SECONDS;
+
+    const ENEMY_RADIUS: f32 = 25.;
+    const ENEMY_SPEED: f32 = 200.;
+
+    /// Rapier without a window or renderer, stepping a fixed 60th of a second on every update.
+    fn physics_app() -> App {
+        let mut app = App::new();
+        app
+            .add_plugins((MinimalPlugins, TransformPlugin, AssetPlugin::default()))
+            .init_asset::<Mesh>()
+            .add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.))
+            .insert_resource(RapierConfiguration {
+                gravity: Vec2::ZERO,
+                timestep_mode: TimestepMode::Fixed { dt: 1. / 60., substeps: 1 },
+                ..default()
+            })
+        ;
+        app
+    }
+
+    fn spawn_enemy(app: &mut App, position: Vec2, target: Option<Vec2>) -> Entity {
+        let mut path_follower = PathFollower::new(ENEMY_REPATH_SECONDS);
+        path_follower.target = target;
+
+        app.world
+            .spawn((
+                TransformBundle::from(Transform::from_translation(position.extend(0.))),
+                RigidBody::Dynamic,
+                Velocity::zero(),
+                LockedAxes::ROTATION_LOCKED,
+                Collider::ball(ENEMY_RADIUS),
+                Enemy::new("grunt".to_string(), ENEMY_SPEED),
+                path_follower,
+            ))
+            .id()
+    }
+
+    #[test]
+    fn enemy_chasing_across_a_wall_stops_at_it() {
+        const WALL_FACE_X: f32 = 100.;
+
+        let mut app = physics_app();
+        app.add_systems(Update, follow_path);
+
+        let wall_half_extents = Vec2::new(50., 500.);
+        app.world.spawn((
+            TransformBundle::from(Transform::from_xyz(WALL_FACE_X + wall_half_extents.x, 0., 0.)),
+            Collider::cuboid(wall_half_extents.x, wall_half_extents.y),
+            RigidBody::Fixed,
+            Wall,
+        ));
+        // Without a nav grid the enemy heads straight for the target on the other side of the wall.
+        let enemy = spawn_enemy(&mut app, Vec2::ZERO, Some(Vec2::new(400., 0.)));
+
+        let mut x = 0.;
+        for _ in 0..300 {
+            app.update();
+
+            x = app.world.get::<Transform>(enemy).unwrap().translation.x;
+            assert!(x + ENEMY_RADIUS <= WALL_FACE_X + 1., "enemy went into the wall, reaching x = {x}");
+        }
+
+        assert!(x + ENEMY_RADIUS >= WALL_FACE_X - 5., "enemy stopped short of the wall at x = {x}");
+    }
+
+    /// The AI systems in a headless app already in `AppState::Playing`, with an empty physics world
+    /// so nothing blocks line of sight.
+    fn ai_app() -> App {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins)
+            .add_state::<AppState>()
+            .insert_resource(NextState(Some(AppState::Playing)))
+            .init_resource::<RapierContext>()
+            .add_event::<Gunshot>()
+            .add_event::<DamageEvent>()
+            .add_plugins(EnemyAiPlugin)
+        ;
+        app
+    }
+
+    fn ai_params() -> AiParams {
+        AiParams {
+            view_distance: 300.,
+            field_of_view: 90.,
+            hearing_radius: 200.,
+            idle_seconds: 1.,
+            patrol_seconds: 5.,
+            patrol_radius: 100.,
+            search_seconds: 3.,
+            flee_health_fraction: 0.25,
+        }
+    }
+
+    fn spawn_player(app: &mut App, position: Vec2) -> Entity {
+        app.world
+            .spawn((
+                Player::default(),
+                Transform::from_translation(position.extend(0.)),
+                Health::new(100),
+                Invulnerability::new(1.),
+            ))
+            .id()
+    }
+
+    /// Enemy at the origin facing up, starting out in `state`.
+    fn spawn_ai_enemy(app: &mut App, state: EnemyState, health: Health) -> Entity {
+        let params = ai_params();
+        let mut ai = EnemyAi::new(Vec2::ZERO, params);
+        ai.transition(state);
+
+        app.world
+            .spawn((
+                Enemy::new("grunt".to_string(), ENEMY_SPEED),
+                Perception::new(params.view_distance, params.field_of_view, params.hearing_radius),
+                EnemyAttack::new(AttackStats {
+                    range: 30.,
+                    damage: 5,
+                    cooldown: 1.,
+                    projectile: None,
+                }),
+                Transform::default(),
+                Velocity::zero(),
+                health,
+                ai,
+                PathFollower::new(ENEMY_REPATH_SECONDS),
+            ))
+            .id()
+    }
+
+    fn state(app: &App, enemy: Entity) -> EnemyState {
+        app.world.get::<EnemyAi>(enemy).unwrap().state
+    }
+
+    #[test]
+    fn idle_enemy_chases_player_it_sees() {
+        let mut app = ai_app();
+        spawn_player(&mut app, Vec2::new(0., 100.));
+        let enemy = spawn_ai_enemy(&mut app, EnemyState::Idle, Health::new(100));
+
+        app.update();
+
+        assert_eq!(state(&app, enemy), EnemyState::Chase);
+    }
+
+    #[test]
+    fn chasing_enemy_searches_after_losing_sight() {
+        let mut app = ai_app();
+        let player = spawn_player(&mut app, Vec2::new(0., 100.));
+        let enemy = spawn_ai_enemy(&mut app, EnemyState::Chase, Health::new(100));
+
+        app.update();
+        assert_eq!(state(&app, enemy), EnemyState::Chase);
+
+        app.world.get_mut::<Transform>(player).unwrap().translation.y = 1000.;
+        app.update();
+
+        assert_eq!(state(&app, enemy), EnemyState::Search);
+        assert_eq!(
+            app.world.get::<Enemy>(enemy).unwrap().last_known_player_position,
+            Some(Vec2::new(0., 100.))
+        );
+    }
+
+    #[test]
+    fn badly_hurt_enemy_flees_until_healed() {
+        let mut app = ai_app();
+        spawn_player(&mut app, Vec2::new(0., 100.));
+        let mut health = Health::new(100);
+        health.take_damage(80);
+        let enemy = spawn_ai_enemy(&mut app, EnemyState::Chase, health);
+
+        app.update();
+        assert_eq!(state(&app, enemy), EnemyState::Flee);
+
+        app.world.get_mut::<Health>(enemy).unwrap().heal(50);
+        app.update();
+
+        assert_eq!(state(&app, enemy), EnemyState::Idle);
+    }
+}
@@ -0,0 +1,113 @@
+use bevy::asset::RecursiveDependencyLoadState;
+use bevy::prelude::*;
+
+use crate::plugins::input::ActionState;
+use crate::resources::app_state::AppState;
+use crate::resources::input_bindings::InputAction;
+use crate::resources::locale::Locale;
+use crate::systems::cleanup::despawn_with;
+
+#[derive(Component)]
+pub struct StateScreen;
+
+/// Assets that have to finish loading before a run can start.
+#[derive(Default, Resource)]
+pub struct LoadingAssets(pub(crate) Vec<UntypedHandle>);
+
+pub struct GameStatePlugin;
+
+impl Plugin for GameStatePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_state::<AppState>()
+            .init_resource::<LoadingAssets>()
+            .add_systems(OnEnter(AppState::Loading), spawn_state_screen("Loading..."))
+            .add_systems(OnEnter(AppState::Paused), spawn_state_screen("Paused\n\nPress Escape to resume"))
+            .add_systems(OnEnter(AppState::GameOver), spawn_state_screen("Game over\n\nPress Enter to return to the menu"))
+            .add_systems(OnExit(AppState::Loading), despawn_with::<StateScreen>)
+            .add_systems(OnExit(AppState::Paused), despawn_with::<StateScreen>)
+            .add_systems(OnExit(AppState::GameOver), despawn_with::<StateScreen>)
+            .add_systems(Update, finish_loading.run_if(in_state(AppState::Loading)))
+            .add_systems(Update, toggle_pause.run_if(in_state(AppState::Playing).or_else(in_state(AppState::Paused))))
+            .add_systems(Update, leave_game_over.run_if(in_state(AppState::GameOver)))
+        ;
+    }
+}
+
+fn spawn_state_screen(text: &'static str) -> impl Fn(Commands, Res<Locale>) {
+    move |mut commands: Commands, locale: Res<Locale>| {
+        commands
+            .spawn(NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(StateScreen)
+            .with_children(|parent| {
+                parent.spawn(
+                    TextBundle::from_section(
+                        locale.tr(text),
+                        TextStyle {
+                            font_size: 48.,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    )
+                    .with_text_alignment(TextAlignment::Center),
+                );
+            });
+    }
+}
+
+fn finish_loading(
+    asset_server: Res<AssetServer>,
+    loading_assets: Res<LoadingAssets>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    // A broken asset never finishes loading, so give up on the run instead of waiting forever.
+    let failed = loading_assets.0.iter().find(|handle| {
+        asset_server.get_recursive_dependency_load_state(handle.id()) == Some(RecursiveDependencyLoadState::Failed)
+    });
+    if let Some(handle) = failed {
+        let path = asset_server.get_path(handle.id()).map_or_else(|| "an asset".to_string(), |path| path.to_string());
+        error!("could not load {path}, returning to the main menu");
+        next_state.set(AppState::MainMenu);
+        return;
+    }
+
+    if loading_assets
+        .0
+        .iter()
+        .all(|handle| asset_server.is_loaded_with_dependencies(handle.id()))
+    {
+        next_state.set(AppState::Playing);
+    }
+}
+
+fn toggle_pause(
+    action_state: Res<ActionState>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if action_state.just_pressed(InputAction::Pause) {
+        match state.get() {
+            AppState::Playing => next_state.set(AppState::Paused),
+            AppState::Paused => next_state.set(AppState::Playing),
+            _ => {}
+        }
+    }
+}
+
+fn leave_game_over(
+    action_state: Res<ActionState>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if action_state.just_pressed(InputAction::Confirm) {
+        next_state.set(AppState::MainMenu);
+    }
+}
@@ -0,0 +1,381 @@
+use bevy::prelude::*;
+
+use crate::components::buff::Buffs;
+use crate::components::enemy::Enemy;
+use crate::components::health::Health;
+use crate::components::player::Player;
+use crate::components::weapon::Inventory;
+use crate::plugins::score::Score;
+use crate::plugins::wave::{WaveDirector, WaveList, WaveListHandle};
+use crate::resources::app_state::AppState;
+use crate::resources::locale::Locale;
+use crate::systems::cleanup::despawn_with;
+
+const HUD_FONT_SIZE: f32 = 24.;
+const HEALTH_BAR_WIDTH: f32 = 240.;
+const HEALTH_BAR_HEIGHT: f32 = 20.;
+
+#[derive(Component)]
+pub struct Hud;
+
+#[derive(Component)]
+pub struct HealthBarFill;
+
+#[derive(Component)]
+pub struct HealthText;
+
+#[derive(Component)]
+pub struct WeaponText;
+
+#[derive(Component)]
+pub struct ScoreText;
+
+#[derive(Component)]
+pub struct WaveText;
+
+#[derive(Component)]
+pub struct BuffsText;
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnExit(AppState::Loading), spawn_hud)
+            .add_systems(OnEnter(AppState::MainMenu), despawn_with::<Hud>)
+            .add_systems(
+                Update,
+                (update_health, update_weapon, update_score, update_wave, update_buffs)
+                    .run_if(in_state(AppState::Playing)),
+            )
+        ;
+    }
+}
+
+fn hud_text(marker: impl Component) -> impl Bundle {
+    (
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: HUD_FONT_SIZE,
+                color: Color::WHITE,
+                ..default()
+            },
+        ),
+        marker,
+    )
+}
+
+fn corner(top: bool, left: bool) -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            top: if top { Val::Px(16.) } else { Val::Auto },
+            bottom: if top { Val::Auto } else { Val::Px(16.) },
+            left: if left { Val::Px(16.) } else { Val::Auto },
+            right: if left { Val::Auto } else { Val::Px(16.) },
+            flex_direction: FlexDirection::Column,
+            align_items: if left { AlignItems::FlexStart } else { AlignItems::FlexEnd },
+            row_gap: Val::Px(4.),
+            ..default()
+        },
+        ..default()
+    }
+}
+
+fn spawn_hud(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Hud)
+        .with_children(|parent| {
+            parent.spawn(corner(true, true)).with_children(|parent| {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            width: Val::Px(HEALTH_BAR_WIDTH),
+                            height: Val::Px(HEALTH_BAR_HEIGHT),
+                            ..default()
+                        },
+                        background_color: Color::rgba(0., 0., 0., 0.6).into(),
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn((
+                            NodeBundle {
+                                style: Style {
+                                    width: Val::Percent(100.),
+                                    height: Val::Percent(100.),
+                                    ..default()
+                                },
+                                background_color: Color::rgb(0.8, 0.1, 0.1).into(),
+                                ..default()
+                            },
+                            HealthBarFill,
+                        ));
+                    });
+                parent.spawn(hud_text(HealthText));
+            });
+            parent.spawn(corner(true, false)).with_children(|parent| {
+                parent.spawn(hud_text(ScoreText));
+                parent.spawn(hud_text(WaveText));
+            });
+            parent.spawn(corner(false, true)).with_children(|parent| {
+                parent.spawn(hud_text(WeaponText));
+            });
+            parent.spawn(corner(false, false)).with_children(|parent| {
+                parent.spawn(hud_text(BuffsText));
+            });
+        });
+}
+
+/// Only touches the text when it changed, so UI layout is not redone every frame.
+fn set_text(text: &mut Text, value: String) {
+    if text.sections[0].value != value {
+        text.sections[0].value = value;
+    }
+}
+
+fn update_health(
+    health_query: Query<&Health, With<Player>>,
+    mut fill_query: Query<&mut Style, With<HealthBarFill>>,
+    mut text_query: Query<&mut Text, With<HealthText>>,
+) {
+    let Ok(health) = health_query.get_single() else {
+        return;
+    };
+    let fraction = health.fraction();
+
+    for mut style in fill_query.iter_mut() {
+        let width = Val::Percent(fraction * 100.);
+        if style.width != width {
+            style.width = width;
+        }
+    }
+    for mut text in text_query.iter_mut() {
+        set_text(&mut text, format!("{} / {}", health.current, health.max));
+    }
+}
+
+fn update_weapon(
+    locale: Res<Locale>,
+    inventory_query: Query<&Inventory, With<Player>>,
+    mut text_query: Query<&mut Text, With<WeaponText>>,
+) {
+    let Ok(inventory) = inventory_query.get_single() else {
+        return;
+    };
+    let weapon = inventory.current();
+    let status = if weapon.is_reloading() {
+        format!("  {}", locale.tr("reloading..."))
+    } else {
+        String::new()
+    };
+
+    for mut text in text_query.iter_mut() {
+        set_text(
+            &mut text,
+            format!("{}  {} / {}{}", locale.tr(weapon.kind.name()), weapon.magazine, weapon.reserve, status),
+        );
+    }
+}
+
+fn update_score(
+    locale: Res<Locale>,
+    score: Res<Score>,
+    player_query: Query<&Player>,
+    mut text_query: Query<&mut Text, With<ScoreText>>,
+) {
+    let zlotowki = player_query.get_single().map_or(0, |player| player.zlotowki);
+
+    for mut text in text_query.iter_mut() {
+        set_text(
+            &mut text,
+            format!(
+                "{} {}  {} {}  {} zł",
+                locale.tr("Score"),
+                score.points,
+                locale.tr("Kills"),
+                score.kills,
+                zlotowki
+            ),
+        );
+    }
+}
+
+fn update_wave(
+    locale: Res<Locale>,
+    director: Res<WaveDirector>,
+    waves_handle: Res<WaveListHandle>,
+    wave_lists: Res<Assets<WaveList>>,
+    enemy_query: Query<(), With<Enemy>>,
+    mut text_query: Query<&mut Text, With<WaveText>>,
+) {
+    let total = wave_lists.get(&waves_handle.0).map_or(0, |wave_list| wave_list.waves.len());
+    let alive = enemy_query.iter().count();
+
+    let objective = if director.started == 0 {
+        locale.tr("Get ready").to_string()
+    } else if director.started == total && director.is_clear() {
+        locale.tr("All waves cleared").to_string()
+    } else {
+        format!(
+            "{} {} / {}  {} {}",
+            locale.tr("Wave"),
+            director.started,
+            total,
+            locale.tr("Enemies left"),
+            alive
+        )
+    };
+
+    for mut text in text_query.iter_mut() {
+        set_text(&mut text, objective.clone());
+    }
+}
+
+fn update_buffs(
+    locale: Res<Locale>,
+    buffs_query: Query<&Buffs, With<Player>>,
+    mut text_query: Query<&mut Text, With<BuffsText>>,
+) {
+    let Ok(buffs) = buffs_query.get_single() else {
+        return;
+    };
+    let lines: Vec<String> = buffs
+        .0
+        .iter()
+        .map(|buff| {
+            format!(
+                "{} x{:.1}  {:.0}s",
+                locale.tr(buff.kind.name()),
+                buff.multiplier,
+                buff.timer.remaining_secs().ceil()
+            )
+        })
+        .collect();
+
+    for mut text in text_query.iter_mut() {
+        set_text(&mut text, lines.join("\n"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::weapon::{Weapon, WeaponKind};
+    use crate::plugins::wave::{Wave, WaveStart};
+    use crate::resources::settings::Language;
+
+    /// The HUD and its update systems without any UI rendering or state gating.
+    fn hud_app() -> (App, Entity) {
+        let mut app = App::new();
+        app
+            .add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<WaveList>()
+            .init_resource::<Score>()
+            .init_resource::<WaveDirector>()
+            .init_resource::<Locale>()
+            .add_systems(Startup, spawn_hud)
+            .add_systems(Update, (update_health, update_weapon, update_score, update_wave, update_buffs))
+        ;
+
+        let wave = Wave {
+            start: WaveStart::Cleared,
+            delay: 0.,
+            spawn_interval: 1.,
+            groups: Vec::new(),
+        };
+        let waves = app.world.resource_mut::<Assets<WaveList>>().add(WaveList {
+            waves: vec![wave.clone(), wave],
+        });
+        app.insert_resource(WaveListHandle(waves));
+
+        let player = app
+            .world
+            .spawn((
+                Player::default(),
+                Health::new(100),
+                Inventory::new(Weapon::new(WeaponKind::Pistol)),
+            ))
+            .id();
+
+        app.update();
+        (app, player)
+    }
+
+    fn text<T: Component>(app: &mut App) -> String {
+        let mut query = app.world.query_filtered::<&Text, With<T>>();
+        query.single(&app.world).sections[0].value.clone()
+    }
+
+    #[test]
+    fn health_text_follows_player_health() {
+        let (mut app, player) = hud_app();
+        assert_eq!(text::<HealthText>(&mut app), "100 / 100");
+
+        app.world.get_mut::<Health>(player).unwrap().take_damage(30);
+        app.update();
+
+        assert_eq!(text::<HealthText>(&mut app), "70 / 100");
+    }
+
+    #[test]
+    fn score_text_follows_score_and_money() {
+        let (mut app, player) = hud_app();
+        assert_eq!(text::<ScoreText>(&mut app), "Score 0  Kills 0  0 zł");
+
+        *app.world.resource_mut::<Score>() = Score { points: 150, kills: 3 };
+        app.world.get_mut::<Player>(player).unwrap().zlotowki = 20;
+        app.update();
+
+        assert_eq!(text::<ScoreText>(&mut app), "Score 150  Kills 3  20 zł");
+    }
+
+    #[test]
+    fn weapon_text_follows_selected_weapon() {
+        let (mut app, player) = hud_app();
+
+        let mut inventory = app.world.get_mut::<Inventory>(player).unwrap();
+        inventory.give(WeaponKind::Shotgun);
+        let weapon = inventory.current_mut();
+        weapon.magazine -= 1;
+        weapon.start_reload();
+        let expected = format!("Shotgun  {} / {}  reloading...", weapon.magazine, weapon.reserve);
+        app.update();
+
+        assert_eq!(text::<WeaponText>(&mut app), expected);
+    }
+
+    #[test]
+    fn wave_text_follows_wave_director() {
+        let (mut app, _) = hud_app();
+        assert_eq!(text::<WaveText>(&mut app), "Get ready");
+
+        app.insert_resource(WaveDirector::resumed(1, vec![0], Vec::new(), 1.));
+        app.world.spawn(Enemy::new("grunt".to_string(), 100.));
+        app.update();
+        assert_eq!(text::<WaveText>(&mut app), "Wave 1 / 2  Enemies left 1");
+
+        app.insert_resource(WaveDirector::resumed(2, Vec::new(), Vec::new(), 1.));
+        app.update();
+        assert_eq!(text::<WaveText>(&mut app), "All waves cleared");
+    }
+
+    #[test]
+    fn hud_text_follows_language() {
+        let (mut app, _) = hud_app();
+
+        app.insert_resource(Locale(Language::Polish));
+        app.update();
+
+        assert_eq!(text::<WaveText>(&mut app), "Przygotuj się");
+        assert_eq!(text::<WeaponText>(&mut app), "Pistolet  12 / 24");
+    }
+}
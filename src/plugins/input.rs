@@ -0,0 +1,256 @@
+use bevy::input::mouse::{MouseMotion, MouseWheel};
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+use crate::plugins::main_menu::{menu_button, MenuAction, MenuActivated, MenuButton, MenuSystems};
+use crate::resources::constants::GAMEPAD_STICK_DEADZONE;
+use crate::resources::input_bindings::{
+    Binding, InputAction, InputBindings, BINDABLE_GAMEPAD_BUTTONS, BINDABLE_KEYS, BINDABLE_MOUSE_BUTTONS,
+};
+use crate::resources::locale::Locale;
+
+/// Device the player used last, which decides whether the mouse or the right stick aims.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputDevice {
+    #[default]
+    KeyboardMouse,
+    Gamepad,
+}
+
+/// Actions held and newly triggered this frame, resolved from `InputBindings` once per frame so
+/// gameplay systems never look at raw keys or buttons.
+#[derive(Resource, Default)]
+pub struct ActionState {
+    pressed: HashSet<InputAction>,
+    just_pressed: HashSet<InputAction>,
+    /// Left stick, with the deadzone removed; added on top of the movement actions.
+    pub(crate) move_stick: Vec2,
+    /// Right stick, with the deadzone removed.
+    pub(crate) aim_stick: Vec2,
+    pub(crate) device: InputDevice,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: InputAction) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: InputAction) -> bool {
+        self.just_pressed.contains(&action)
+    }
+}
+
+/// Action waiting for the player to press the input it should be bound to.
+#[derive(Resource, Default)]
+pub struct Rebinding(pub(crate) Option<InputAction>);
+
+pub struct InputPlugin;
+
+impl Plugin for InputPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .insert_resource(InputBindings::load())
+            .init_resource::<ActionState>()
+            .init_resource::<Rebinding>()
+            .add_systems(PreUpdate, update_action_state.after(InputSystem))
+            .add_systems(
+                Update,
+                (
+                    start_rebinding,
+                    capture_rebinding,
+                    // Inserting the bindings read from disk counts as a change, but needs no writing back.
+                    save_bindings.run_if(
+                        resource_changed::<InputBindings>().and_then(not(resource_added::<InputBindings>())),
+                    ),
+                    update_binding_labels.run_if(
+                        resource_changed::<InputBindings>().or_else(resource_changed::<Rebinding>()),
+                    ),
+                )
+                    .chain()
+                    .after(MenuSystems),
+            )
+        ;
+    }
+}
+
+fn update_action_state(
+    bindings: Res<InputBindings>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    mut mouse_motion_events: EventReader<MouseMotion>,
+    mut action_state: ResMut<ActionState>,
+) {
+    let scroll: f32 = mouse_wheel_events.read().map(|event| event.y).sum();
+    let mouse_moved = mouse_motion_events.read().count() > 0;
+    let stick = |x, y| {
+        let stick = gamepads
+            .iter()
+            .map(|gamepad| {
+                Vec2::new(
+                    gamepad_axes.get(GamepadAxis::new(gamepad, x)).unwrap_or(0.),
+                    gamepad_axes.get(GamepadAxis::new(gamepad, y)).unwrap_or(0.),
+                )
+            })
+            .fold(Vec2::ZERO, |sum, stick| sum + stick);
+        apply_deadzone(stick, GAMEPAD_STICK_DEADZONE)
+    };
+    action_state.move_stick = stick(GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY);
+    action_state.aim_stick = stick(GamepadAxisType::RightStickX, GamepadAxisType::RightStickY);
+
+    let gamepad_input = |button_type, check: fn(&Input<GamepadButton>, GamepadButton) -> bool| {
+        gamepads
+            .iter()
+            .any(|gamepad| check(&gamepad_buttons, GamepadButton::new(gamepad, button_type)))
+    };
+
+    // The wheel has no held state, so a notch counts as both pressed and just pressed.
+    let state = |binding: &Binding| match *binding {
+        Binding::Key(key) => (keyboard_input.pressed(key), keyboard_input.just_pressed(key)),
+        Binding::Mouse(button) => (mouse_button_input.pressed(button), mouse_button_input.just_pressed(button)),
+        Binding::Gamepad(button_type) => (
+            gamepad_input(button_type, Input::pressed),
+            gamepad_input(button_type, Input::just_pressed),
+        ),
+        Binding::Wheel(up) => {
+            let scrolled = if up { scroll > 0. } else { scroll < 0. };
+            (scrolled, scrolled)
+        }
+    };
+
+    action_state.pressed.clear();
+    action_state.just_pressed.clear();
+
+    for action in InputAction::ALL {
+        for (pressed, just_pressed) in bindings.get(action).iter().map(&state) {
+            if pressed {
+                action_state.pressed.insert(action);
+            }
+            if just_pressed {
+                action_state.just_pressed.insert(action);
+            }
+        }
+    }
+
+    let gamepad_used = gamepad_buttons.get_just_pressed().next().is_some()
+        || action_state.move_stick != Vec2::ZERO
+        || action_state.aim_stick != Vec2::ZERO;
+    let keyboard_mouse_used = keyboard_input.get_just_pressed().next().is_some()
+        || mouse_button_input.get_just_pressed().next().is_some()
+        || mouse_moved
+        || scroll != 0.;
+
+    if keyboard_mouse_used {
+        action_state.device = InputDevice::KeyboardMouse;
+    } else if gamepad_used {
+        action_state.device = InputDevice::Gamepad;
+    }
+}
+
+/// Zeroes stick input inside the `deadzone` radius and rescales the rest, so the response starts
+/// at zero on its edge and still reaches full deflection.
+fn apply_deadzone(stick: Vec2, deadzone: f32) -> Vec2 {
+    let length = stick.length();
+    if length <= deadzone {
+        return Vec2::ZERO;
+    }
+
+    stick / length * ((length - deadzone) / (1. - deadzone)).min(1.)
+}
+
+/// Adds a button per action, numbered from `first_index` in the menu focus order.
+pub fn spawn_binding_buttons(parent: &mut ChildBuilder, bindings: &InputBindings, locale: &Locale, first_index: usize) {
+    for (offset, action) in InputAction::rebindable().enumerate() {
+        menu_button(parent, &bindings.label(action, locale), MenuAction::Rebind(action), first_index + offset);
+    }
+}
+
+fn start_rebinding(
+    mut menu_activated_events: EventReader<MenuActivated>,
+    mut bindings: ResMut<InputBindings>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    for MenuActivated(action) in menu_activated_events.read() {
+        match action {
+            MenuAction::Rebind(action) => rebinding.0 = Some(*action),
+            MenuAction::ResetBindings => *bindings = InputBindings::default(),
+            _ => {}
+        }
+    }
+}
+
+/// Binds the first key, mouse button or gamepad button pressed after a rebind was started.
+/// Escape cancels, so it cannot be bound from the menu.
+fn capture_rebinding(
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    mut bindings: ResMut<InputBindings>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    // The press that started the rebind must not be bound.
+    let Some(action) = rebinding.0 else {
+        return;
+    };
+    if rebinding.is_changed() {
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        rebinding.0 = None;
+        return;
+    }
+
+    let key = BINDABLE_KEYS.into_iter().find(|key| keyboard_input.just_pressed(*key)).map(Binding::Key);
+    let mouse = BINDABLE_MOUSE_BUTTONS
+        .into_iter()
+        .find(|button| mouse_button_input.just_pressed(*button))
+        .map(Binding::Mouse);
+    let gamepad = BINDABLE_GAMEPAD_BUTTONS
+        .into_iter()
+        .find(|button_type| {
+            gamepads
+                .iter()
+                .any(|gamepad| gamepad_buttons.just_pressed(GamepadButton::new(gamepad, *button_type)))
+        })
+        .map(Binding::Gamepad);
+
+    if let Some(binding) = key.or(mouse).or(gamepad) {
+        bindings.rebind(action, binding);
+        rebinding.0 = None;
+    }
+}
+
+fn save_bindings(bindings: Res<InputBindings>) {
+    bindings.save();
+}
+
+fn update_binding_labels(
+    bindings: Res<InputBindings>,
+    locale: Res<Locale>,
+    rebinding: Res<Rebinding>,
+    button_query: Query<(&MenuButton, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (button, children) in button_query.iter() {
+        let MenuAction::Rebind(action) = button.action else {
+            continue;
+        };
+        let label = if rebinding.0 == Some(action) {
+            format!("{}: {}", locale.tr(action.name()), locale.tr("press a key..."))
+        } else {
+            bindings.label(action, &locale)
+        };
+
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = label.clone();
+            }
+        }
+    }
+}
@@ -0,0 +1,379 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+
+use crate::plugins::input::{spawn_binding_buttons, ActionState, Rebinding};
+use crate::plugins::settings::{spawn_setting_buttons, SettingKind};
+use crate::resources::app_state::AppState;
+use crate::resources::input_bindings::{InputAction, InputBindings};
+use crate::resources::locale::Locale;
+use crate::resources::settings::Settings;
+use crate::systems::cleanup::despawn_with;
+
+const MENU_BACKGROUND: &str = "1_map_stare-bielany-v3.png";
+const BUTTON_WIDTH: f32 = 320.;
+const BUTTON_HEIGHT: f32 = 56.;
+/// How far a stick has to be pushed before it moves the menu focus.
+const STICK_THRESHOLD: f32 = 0.5;
+
+const BUTTON_COLOR: Color = Color::rgba(0.1, 0.1, 0.1, 0.8);
+const FOCUSED_BUTTON_COLOR: Color = Color::rgba(0.6, 0.15, 0.15, 0.9);
+
+/// Root of the menu UI; the whole tree is rebuilt whenever the page or the language changes.
+#[derive(Component)]
+pub struct MainMenuScreen;
+
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MenuPage {
+    #[default]
+    Main,
+    Settings,
+    Controls,
+    Credits,
+}
+
+impl MenuPage {
+    /// Page that Back leads to.
+    pub fn parent(&self) -> MenuPage {
+        match self {
+            MenuPage::Controls => MenuPage::Settings,
+            _ => MenuPage::Main,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MenuAction {
+    NewGame,
+    Continue,
+    Settings,
+    Credits,
+    Quit,
+    Back,
+    Setting(SettingKind),
+    Controls,
+    Rebind(InputAction),
+    ResetBindings,
+}
+
+/// Index of the button a keyboard or gamepad press would activate.
+#[derive(Resource, Default)]
+pub struct MenuFocus(pub(crate) usize);
+
+#[derive(Component)]
+pub struct MenuButton {
+    pub(crate) action: MenuAction,
+    /// Position of the button on its page, top to bottom.
+    pub(crate) index: usize,
+}
+
+#[derive(Event)]
+pub struct MenuActivated(pub(crate) MenuAction);
+
+/// Left or right pressed while a button is focused, for buttons holding a value.
+#[derive(Event)]
+pub struct MenuStepped {
+    pub(crate) action: MenuAction,
+    pub(crate) step: i32,
+}
+
+/// Whether there is a saved run the Continue button could resume.
+#[derive(Resource, Default)]
+pub struct SaveAvailable(pub(crate) bool);
+
+/// Systems driving the menu, which input rebinding runs after.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MenuSystems;
+
+/// Sent when Continue is chosen; the save system loads the run.
+#[derive(Event)]
+pub struct ContinueGame;
+
+pub struct MainMenuPlugin;
+
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<MenuPage>()
+            .init_resource::<MenuFocus>()
+            .init_resource::<SaveAvailable>()
+            .add_event::<MenuActivated>()
+            .add_event::<MenuStepped>()
+            .add_event::<ContinueGame>()
+            .add_systems(OnEnter(AppState::MainMenu), open_main_page)
+            .add_systems(OnExit(AppState::MainMenu), despawn_with::<MainMenuScreen>)
+            .add_systems(
+                Update,
+                (
+                    spawn_menu_page.run_if(resource_changed::<MenuPage>().or_else(resource_changed::<Locale>())),
+                    navigate_menu,
+                    point_at_menu,
+                    highlight_focused_button,
+                    run_menu_actions,
+                )
+                    .chain()
+                    .in_set(MenuSystems)
+                    .run_if(in_state(AppState::MainMenu)),
+            )
+        ;
+    }
+}
+
+fn open_main_page(mut page: ResMut<MenuPage>) {
+    *page = MenuPage::Main;
+}
+
+fn menu_text(text: impl Into<String>, font_size: f32) -> TextBundle {
+    TextBundle::from_section(
+        text,
+        TextStyle {
+            font_size,
+            color: Color::WHITE,
+            ..default()
+        },
+    )
+    .with_text_alignment(TextAlignment::Center)
+}
+
+pub(crate) fn menu_button(parent: &mut ChildBuilder, label: &str, action: MenuAction, index: usize) {
+    parent
+        .spawn(ButtonBundle {
+            style: Style {
+                width: Val::Px(BUTTON_WIDTH),
+                height: Val::Px(BUTTON_HEIGHT),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: BUTTON_COLOR.into(),
+            ..default()
+        })
+        .insert(MenuButton { action, index })
+        .with_children(|parent| {
+            parent.spawn(menu_text(label, 28.));
+        });
+}
+
+fn spawn_menu_page(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    page: Res<MenuPage>,
+    save_available: Res<SaveAvailable>,
+    settings: Res<Settings>,
+    bindings: Res<InputBindings>,
+    locale: Res<Locale>,
+    mut focus: ResMut<MenuFocus>,
+    screen_query: Query<Entity, With<MainMenuScreen>>,
+) {
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    // A new language redraws the same page, so the focus stays on the Language setting.
+    if page.is_changed() {
+        focus.0 = 0;
+    }
+
+    commands
+        .spawn(ImageBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..default()
+            },
+            image: UiImage::new(asset_server.load(MENU_BACKGROUND)),
+            ..default()
+        })
+        .insert(MainMenuScreen)
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.),
+                        height: Val::Percent(100.),
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(12.),
+                        ..default()
+                    },
+                    // Darkens the map so the buttons stay readable.
+                    background_color: Color::rgba(0., 0., 0., 0.5).into(),
+                    ..default()
+                })
+                .with_children(|parent| match *page {
+                    MenuPage::Main => {
+                        parent.spawn(menu_text("Stare Bielany", 72.));
+
+                        let mut actions = vec![("New Game", MenuAction::NewGame)];
+                        if save_available.0 {
+                            actions.push(("Continue", MenuAction::Continue));
+                        }
+                        actions.push(("Settings", MenuAction::Settings));
+                        actions.push(("Credits", MenuAction::Credits));
+                        actions.push(("Quit", MenuAction::Quit));
+
+                        for (index, (label, action)) in actions.into_iter().enumerate() {
+                            menu_button(parent, locale.tr(label), action, index);
+                        }
+                    }
+                    MenuPage::Settings => {
+                        parent.spawn(menu_text(locale.tr("Settings"), 56.));
+                        spawn_setting_buttons(parent, &settings, &locale, 0);
+                        menu_button(parent, locale.tr("Controls"), MenuAction::Controls, SettingKind::ALL.len());
+                        menu_button(parent, locale.tr("Back"), MenuAction::Back, SettingKind::ALL.len() + 1);
+                    }
+                    MenuPage::Controls => {
+                        parent.spawn(menu_text(locale.tr("Controls"), 56.));
+                        // Too many actions for one column, so they wrap into a grid.
+                        parent
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    width: Val::Px(3. * BUTTON_WIDTH + 2. * 12.),
+                                    flex_wrap: FlexWrap::Wrap,
+                                    justify_content: JustifyContent::Center,
+                                    column_gap: Val::Px(12.),
+                                    row_gap: Val::Px(12.),
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .with_children(|parent| spawn_binding_buttons(parent, &bindings, &locale, 0));
+                        let count = InputAction::rebindable().count();
+                        menu_button(parent, locale.tr("Reset to defaults"), MenuAction::ResetBindings, count);
+                        menu_button(parent, locale.tr("Back"), MenuAction::Back, count + 1);
+                    }
+                    MenuPage::Credits => {
+                        parent.spawn(menu_text(locale.tr("Credits"), 56.));
+                        parent.spawn(menu_text(
+                            format!(
+                                "Stare Bielany\n\n{}\n{}",
+                                locale.tr("Made with Bevy and Rapier"),
+                                locale.tr("Map drawn in Tiled"),
+                            ),
+                            28.,
+                        ));
+                        menu_button(parent, locale.tr("Back"), MenuAction::Back, 0);
+                    }
+                });
+        });
+}
+
+/// Moves the focus with the menu actions or the left stick, activates it with Confirm and goes
+/// back a page with MenuBack.
+fn navigate_menu(
+    action_state: Res<ActionState>,
+    page: Res<MenuPage>,
+    mut focus: ResMut<MenuFocus>,
+    button_query: Query<&MenuButton>,
+    mut menu_activated_events: EventWriter<MenuActivated>,
+    mut menu_stepped_events: EventWriter<MenuStepped>,
+    rebinding: Res<Rebinding>,
+    mut stick_held: Local<bool>,
+) {
+    let count = button_query.iter().count();
+    // While rebinding, the next press belongs to the binding.
+    if count == 0 || rebinding.0.is_some() {
+        return;
+    }
+
+    let stick = action_state.move_stick;
+    let stick_x = Some(stick.x).filter(|value| value.abs() >= STICK_THRESHOLD);
+    let stick_y = Some(stick.y).filter(|value| value.abs() >= STICK_THRESHOLD);
+    // The stick moves the focus once per push rather than every frame it is held.
+    let (stick_step_x, stick_step_y) = if *stick_held {
+        (0, 0)
+    } else {
+        (stick_x.map_or(0, |value| value.signum() as i32), stick_y.map_or(0, |value| value.signum() as i32))
+    };
+    *stick_held = stick_x.is_some() || stick_y.is_some();
+
+    let up = action_state.just_pressed(InputAction::MenuUp) || stick_step_y > 0;
+    let down = action_state.just_pressed(InputAction::MenuDown) || stick_step_y < 0;
+    let left = action_state.just_pressed(InputAction::MenuLeft) || stick_step_x < 0;
+    let right = action_state.just_pressed(InputAction::MenuRight) || stick_step_x > 0;
+
+    if up {
+        focus.0 = (focus.0 + count - 1) % count;
+    }
+    if down {
+        focus.0 = (focus.0 + 1) % count;
+    }
+
+    let focused = button_query.iter().find(|button| button.index == focus.0);
+
+    if let Some(button) = focused {
+        if action_state.just_pressed(InputAction::Confirm) {
+            menu_activated_events.send(MenuActivated(button.action));
+        }
+        if left != right {
+            menu_stepped_events.send(MenuStepped {
+                action: button.action,
+                step: if right { 1 } else { -1 },
+            });
+        }
+    }
+
+    if *page != MenuPage::Main && action_state.just_pressed(InputAction::MenuBack) {
+        menu_activated_events.send(MenuActivated(MenuAction::Back));
+    }
+}
+
+/// Hovering a button focuses it, clicking activates it.
+fn point_at_menu(
+    interaction_query: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    rebinding: Res<Rebinding>,
+    mut focus: ResMut<MenuFocus>,
+    mut menu_activated_events: EventWriter<MenuActivated>,
+) {
+    if rebinding.0.is_some() {
+        return;
+    }
+
+    for (interaction, button) in interaction_query.iter() {
+        match interaction {
+            Interaction::Hovered => focus.0 = button.index,
+            Interaction::Pressed => {
+                focus.0 = button.index;
+                menu_activated_events.send(MenuActivated(button.action));
+            }
+            Interaction::None => {}
+        }
+    }
+}
+
+fn highlight_focused_button(
+    focus: Res<MenuFocus>,
+    mut button_query: Query<(&MenuButton, &mut BackgroundColor)>,
+) {
+    for (button, mut background) in button_query.iter_mut() {
+        let color = if button.index == focus.0 { FOCUSED_BUTTON_COLOR } else { BUTTON_COLOR };
+        if background.0 != color {
+            background.0 = color;
+        }
+    }
+}
+
+fn run_menu_actions(
+    mut menu_activated_events: EventReader<MenuActivated>,
+    mut page: ResMut<MenuPage>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut continue_events: EventWriter<ContinueGame>,
+    mut exit_events: EventWriter<AppExit>,
+) {
+    // Only the first action counts, the page it was on is gone afterwards.
+    let Some(&MenuActivated(action)) = menu_activated_events.read().next() else {
+        return;
+    };
+
+    match action {
+        MenuAction::NewGame => next_state.set(AppState::Loading),
+        MenuAction::Continue => continue_events.send(ContinueGame),
+        MenuAction::Settings => *page = MenuPage::Settings,
+        MenuAction::Credits => *page = MenuPage::Credits,
+        MenuAction::Quit => exit_events.send(AppExit),
+        MenuAction::Controls => *page = MenuPage::Controls,
+        MenuAction::Back => *page = page.parent(),
+        // Handled by the settings and input plugins, which keep the page open.
+        MenuAction::Setting(_) | MenuAction::Rebind(_) | MenuAction::ResetBindings => return,
+    }
+    menu_activated_events.clear();
+}
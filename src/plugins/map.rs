@@ -0,0 +1,147 @@
+use bevy::app::{App, Plugin, Startup, Update};
+use bevy::asset::{AssetApp, AssetEvent, AssetServer, Assets, Handle};
+use bevy::core::Name;
+use bevy::hierarchy::{BuildChildren, DespawnRecursiveExt};
+use bevy::math::{Quat, Vec2, Vec3};
+use bevy::prelude::{
+    Color, Commands, Entity, Event, EventReader, EventWriter, Query, Res, ResMut, Resource, SpatialBundle,
+    SpriteSheetBundle, TextureAtlas, TextureAtlasSprite, Transform, With,
+};
+use bevy::sprite::Anchor;
+
+use crate::components::map::MapLayer;
+use crate::helpers::map_loader::{TiledMap, TiledMapLoader};
+use crate::plugins::game_state::LoadingAssets;
+use crate::resources::constants::{MAP_PATH, MAP_SCALE};
+
+/// Distance on the z axis between two consecutive map layers.
+const LAYER_Z_STEP: f32 = 0.01;
+
+#[derive(Resource)]
+pub struct CurrentMap(pub(crate) Handle<TiledMap>);
+
+/// Sent every time the current map is (re)spawned, so other plugins can build on its data.
+#[derive(Event)]
+pub struct MapLoaded;
+
+pub struct MapPlugin;
+
+impl Plugin for MapPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_asset::<TiledMap>()
+            .init_asset_loader::<TiledMapLoader>()
+            .add_event::<MapLoaded>()
+            .add_systems(Startup, load_map)
+            .add_systems(Update, spawn_map_layers)
+        ;
+    }
+}
+
+fn load_map(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading_assets: ResMut<LoadingAssets>,
+) {
+    let map = asset_server.load(MAP_PATH);
+    loading_assets.0.push(map.clone().untyped());
+    commands.insert_resource(CurrentMap(map));
+}
+
+fn spawn_map_layers(
+    mut commands: Commands,
+    mut map_events: EventReader<AssetEvent<TiledMap>>,
+    mut map_loaded_events: EventWriter<MapLoaded>,
+    current_map: Res<CurrentMap>,
+    maps: Res<Assets<TiledMap>>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    layer_query: Query<Entity, With<MapLayer>>,
+) {
+    let reloaded = map_events.read().any(|event| match event {
+        AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => {
+            *id == current_map.0.id()
+        }
+        _ => false,
+    });
+    if !reloaded {
+        return;
+    }
+
+    let Some(tiled_map) = maps.get(&current_map.0) else {
+        return;
+    };
+    let map = &tiled_map.map;
+
+    for entity in layer_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let atlases: Vec<Handle<TextureAtlas>> = map
+        .tilesets
+        .iter()
+        .zip(tiled_map.images.iter())
+        .map(|(tileset, image)| {
+            texture_atlases.add(TextureAtlas::from_grid(
+                image.clone(),
+                Vec2::new(tileset.tilewidth as f32, tileset.tileheight as f32),
+                tileset.columns as usize,
+                tileset.rows() as usize,
+                Some(Vec2::splat(tileset.spacing as f32)),
+                Some(Vec2::splat(tileset.margin as f32)),
+            ))
+        })
+        .collect();
+
+    for (layer_index, layer) in map.layers.iter().enumerate() {
+        if !layer.is_tile_layer() || !layer.visible || layer.width == 0 || layer.height == 0 {
+            continue;
+        }
+
+        let layer_offset = Vec2::new(layer.offsetx, -layer.offsety) * MAP_SCALE;
+
+        commands
+            .spawn(SpatialBundle::from_transform(Transform::from_xyz(
+                layer_offset.x,
+                layer_offset.y,
+                layer_index as f32 * LAYER_Z_STEP,
+            )))
+            .insert(MapLayer)
+            .insert(Name::new(layer.name.clone()))
+            .with_children(|children| {
+                for (cell, gid) in layer.data.iter().enumerate() {
+                    let Some(tile) = map.resolve_gid(*gid) else {
+                        continue;
+                    };
+
+                    let column = cell as u32 % layer.width;
+                    let row = cell as u32 / layer.width;
+                    let mut position = map.cell_origin(column, row) * MAP_SCALE;
+                    let mut rotation = Quat::IDENTITY;
+
+                    // Turning around the bottom-left anchor swings the tile left of its cell.
+                    if tile.rotated {
+                        position.x += map.tilesets[tile.tileset].tileheight as f32 * MAP_SCALE;
+                        rotation = Quat::from_rotation_z(std::f32::consts::FRAC_PI_2);
+                    }
+
+                    children.spawn(SpriteSheetBundle {
+                        texture_atlas: atlases[tile.tileset].clone(),
+                        sprite: TextureAtlasSprite {
+                            index: tile.index,
+                            flip_x: tile.flip_x,
+                            flip_y: tile.flip_y,
+                            color: Color::rgba(1., 1., 1., layer.opacity),
+                            anchor: Anchor::BottomLeft,
+                            ..Default::default()
+                        },
+                        transform: Transform::from_xyz(position.x, position.y, 0.)
+                            .with_rotation(rotation)
+                            .with_scale(Vec3::splat(MAP_SCALE)),
+                        ..Default::default()
+                    });
+                }
+            });
+    }
+
+    map_loaded_events.send(MapLoaded);
+}
@@ -1,7 +1,20 @@
 pub mod physics;
-pub mod cursor_position;
+pub mod aim;
 pub mod player;
 pub mod pickup;
 pub mod enemy;
+pub mod enemy_ai;
 pub mod bullet;
 pub mod wall;
+pub mod map;
+pub mod game_state;
+pub mod weapon;
+pub mod wave;
+pub mod score;
+pub mod hud;
+pub mod damage_feedback;
+pub mod damage;
+pub mod main_menu;
+pub mod save;
+pub mod settings;
+pub mod input;
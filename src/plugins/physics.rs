@@ -1,7 +1,9 @@
 use bevy::math::Vec2;
-use bevy::prelude::{App, default, Plugin};
+use bevy::prelude::{App, default, OnEnter, OnExit, Plugin, ResMut};
 use bevy_rapier2d::prelude::*;
 
+use crate::resources::app_state::AppState;
+
 const PIXELS_PER_METER: f32 = 100.;
 
 pub struct PhysicsPlugin;
@@ -11,10 +13,21 @@ impl Plugin for PhysicsPlugin {
         app
             .insert_resource(RapierConfiguration {
                 gravity: Vec2::ZERO,
+                physics_pipeline_active: false,
                 ..default()
             })
+            .add_systems(OnEnter(AppState::Playing), resume_physics)
+            .add_systems(OnExit(AppState::Playing), pause_physics)
             .add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(PIXELS_PER_METER))
             .add_plugins(RapierDebugRenderPlugin::default())
         ;
     }
 }
+
+fn resume_physics(mut rapier_configuration: ResMut<RapierConfiguration>) {
+    rapier_configuration.physics_pipeline_active = true;
+}
+
+fn pause_physics(mut rapier_configuration: ResMut<RapierConfiguration>) {
+    rapier_configuration.physics_pipeline_active = false;
+}
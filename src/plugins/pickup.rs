@@ -1,19 +1,87 @@
-use bevy::prelude::{App, Commands, GlobalTransform, Plugin, Startup, Transform};
+use bevy::math::Vec2;
+use bevy::prelude::{
+    in_state, App, Asset, AssetApp, AssetServer, Assets, Commands, Event, EventReader, Handle,
+    IntoSystemConfigs, OnEnter, OnExit, Plugin, Query, Res, ResMut, Resource, Sprite,
+    SpriteBundle, Startup, Time, Transform, Update,
+};
+use bevy::reflect::TypePath;
+use bevy::utils::HashMap;
 use bevy_rapier2d::geometry::{ActiveEvents, Collider, Sensor};
 use rand::{Rng, thread_rng};
+use serde::Deserialize;
 
-use crate::components::pickup::Pickup;
+use crate::components::buff::{BuffKind, Buffs};
+use crate::components::health::Health;
+use crate::components::pickup::{Pickup, PickupKind, WeaponPickup};
+use crate::components::player::Player;
+use crate::components::weapon::{Inventory, WeaponKind};
+use crate::helpers::json_asset::JsonAssetLoader;
+use crate::plugins::enemy::{EnemyArchetypes, EnemyArchetypesHandle, EnemyDied};
+use crate::plugins::game_state::LoadingAssets;
+use crate::resources::app_state::AppState;
+use crate::resources::constants::LOOT_SCATTER;
+use crate::systems::cleanup::despawn_with;
+
+/// What a pickup kind gives the player: health points, ammo rounds, złotówki or a stat multiplier.
+#[derive(Deserialize, Clone, Copy)]
+pub struct PickupEffect {
+    pub(crate) amount: f32,
+    #[serde(default)]
+    pub(crate) duration: f32,
+}
+
+/// Entry of an enemy loot table, rolled independently of the others when the enemy dies.
+#[derive(Deserialize, Clone, Copy)]
+pub struct LootDrop {
+    pub(crate) pickup: PickupKind,
+    /// Which weapon a `weapon` pickup holds.
+    #[serde(default)]
+    pub(crate) weapon: Option<WeaponKind>,
+    pub(crate) chance: f32,
+}
+
+#[derive(Asset, TypePath, Deserialize)]
+pub struct PickupTable(pub(crate) HashMap<PickupKind, PickupEffect>);
+
+#[derive(Resource)]
+pub struct PickupTableHandle(pub(crate) Handle<PickupTable>);
+
+#[derive(Event)]
+pub struct PickupCollected {
+    pub(crate) kind: PickupKind,
+    pub(crate) weapon: Option<WeaponKind>,
+    pub(crate) position: Vec2,
+}
 
 pub struct PickupPlugin;
 
 impl Plugin for PickupPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_systems(Startup, spawn_sample_pickups)
+            .init_asset::<PickupTable>()
+            .register_asset_loader(JsonAssetLoader::<PickupTable>::new(&["pickups.json"]))
+            .add_event::<PickupCollected>()
+            .add_systems(Startup, load_pickup_table)
+            .add_systems(OnExit(AppState::Loading), spawn_sample_pickups)
+            .add_systems(OnEnter(AppState::MainMenu), despawn_with::<Pickup>)
+            .add_systems(
+                Update,
+                (apply_pickup_effects, expire_buffs, drop_loot).run_if(in_state(AppState::Playing)),
+            )
         ;
     }
 }
 
+fn load_pickup_table(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading_assets: ResMut<LoadingAssets>,
+) {
+    let table = asset_server.load("data.pickups.json");
+    loading_assets.0.push(table.clone().untyped());
+    commands.insert_resource(PickupTableHandle(table));
+}
+
 fn spawn_sample_pickups(
     mut commands: Commands
 ) {
@@ -22,16 +90,140 @@ fn spawn_sample_pickups(
     for _ in 0..10 {
         let x = rng.gen_range(-200.0..250.0);
         let y = rng.gen_range(-200.0..250.0);
+        let kind = PickupKind::LOOT[rng.gen_range(0..PickupKind::LOOT.len())];
 
-        commands
-            .spawn((
-                Transform::from_xyz(x, y, 0.),
-                GlobalTransform::default(),
-            ))
-            .insert(Collider::capsule_y(2., 1.2))
-            .insert(ActiveEvents::COLLISION_EVENTS)
-            .insert(Sensor)
-            .insert(Pickup)
-        ;
+        spawn_pickup(&mut commands, kind, Vec2::new(x, y));
+    }
+
+    // The pistol is the starting weapon, so only the others are lying around.
+    for weapon in WeaponKind::ALL.into_iter().skip(1) {
+        let x = rng.gen_range(-200.0..250.0);
+        let y = rng.gen_range(-200.0..250.0);
+
+        spawn_weapon_pickup(&mut commands, weapon, Vec2::new(x, y));
+    }
+}
+
+pub fn spawn_pickup(
+    commands: &mut Commands,
+    kind: PickupKind,
+    position: Vec2,
+) {
+    commands
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                color: kind.color(),
+                custom_size: Some(Vec2::splat(12.)),
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(position.x, position.y, 1.),
+            ..Default::default()
+        })
+        .insert(Collider::capsule_y(2., 1.2))
+        .insert(ActiveEvents::COLLISION_EVENTS)
+        .insert(Sensor)
+        .insert(Pickup { kind })
+    ;
+}
+
+pub fn spawn_weapon_pickup(
+    commands: &mut Commands,
+    weapon: WeaponKind,
+    position: Vec2,
+) {
+    commands
+        .spawn(SpriteBundle {
+            sprite: Sprite {
+                color: weapon.color(),
+                custom_size: Some(weapon.sprite_size()),
+                ..Default::default()
+            },
+            transform: Transform::from_xyz(position.x, position.y, 1.),
+            ..Default::default()
+        })
+        .insert(Collider::cuboid(weapon.sprite_size().x / 2., 6.))
+        .insert(ActiveEvents::COLLISION_EVENTS)
+        .insert(Sensor)
+        .insert(Pickup { kind: PickupKind::Weapon })
+        .insert(WeaponPickup(weapon))
+    ;
+}
+
+fn apply_pickup_effects(
+    mut pickup_collected_events: EventReader<PickupCollected>,
+    pickup_table_handle: Res<PickupTableHandle>,
+    pickup_tables: Res<Assets<PickupTable>>,
+    mut player_query: Query<(&mut Player, &mut Health, &mut Buffs, &mut Inventory)>,
+) {
+    let Some(pickup_table) = pickup_tables.get(&pickup_table_handle.0) else {
+        return;
+    };
+
+    for event in pickup_collected_events.read() {
+        if let Some(weapon) = event.weapon {
+            for (_, _, _, mut inventory) in player_query.iter_mut() {
+                inventory.give(weapon);
+            }
+            continue;
+        }
+
+        let Some(effect) = pickup_table.0.get(&event.kind) else {
+            continue;
+        };
+
+        for (mut player, mut health, mut buffs, mut inventory) in player_query.iter_mut() {
+            match event.kind {
+                PickupKind::Health => health.heal(effect.amount as i32),
+                PickupKind::Currency => player.zlotowki += effect.amount as u32,
+                PickupKind::SpeedBoost => buffs.add(BuffKind::Speed, effect.amount, effect.duration),
+                PickupKind::DamageBoost => buffs.add(BuffKind::Damage, effect.amount, effect.duration),
+                PickupKind::Ammo => inventory.current_mut().add_ammo(effect.amount as u32),
+                PickupKind::Weapon => {}
+            }
+        }
+    }
+}
+
+fn drop_loot(
+    mut commands: Commands,
+    mut enemy_died_events: EventReader<EnemyDied>,
+    archetypes_handle: Res<EnemyArchetypesHandle>,
+    archetypes: Res<Assets<EnemyArchetypes>>,
+) {
+    let Some(archetypes) = archetypes.get(&archetypes_handle.0) else {
+        return;
+    };
+    let mut rng = thread_rng();
+
+    for event in enemy_died_events.read() {
+        let Some(archetype) = archetypes.0.get(&event.archetype) else {
+            continue;
+        };
+
+        for drop in archetype.loot.iter().filter(|drop| rng.gen::<f32>() < drop.chance) {
+            let position = event.position
+                + Vec2::new(
+                    rng.gen_range(-LOOT_SCATTER..=LOOT_SCATTER),
+                    rng.gen_range(-LOOT_SCATTER..=LOOT_SCATTER),
+                );
+
+            match (drop.pickup, drop.weapon) {
+                (PickupKind::Weapon, Some(weapon)) => spawn_weapon_pickup(&mut commands, weapon, position),
+                (PickupKind::Weapon, None) => {}
+                (kind, _) => spawn_pickup(&mut commands, kind, position),
+            }
+        }
+    }
+}
+
+fn expire_buffs(
+    time: Res<Time>,
+    mut buffs_query: Query<&mut Buffs>,
+) {
+    for mut buffs in buffs_query.iter_mut() {
+        for buff in buffs.0.iter_mut() {
+            buff.timer.tick(time.delta());
+        }
+        buffs.0.retain(|buff| !buff.timer.finished());
     }
 }
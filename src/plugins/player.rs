@@ -2,13 +2,28 @@ use benimator::FrameRate;
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
-use crate::components::bullet::Bullet;
-use crate::components::pickup::Pickup;
+use crate::components::buff::{BuffKind, Buffs};
+use crate::components::damage::DamageKind;
+use crate::components::enemy::Enemy;
+use crate::components::faction::Faction;
+use crate::components::health::Health;
+use crate::components::invulnerability::Invulnerability;
+use crate::components::pickup::{Pickup, WeaponPickup};
 use crate::components::player::Player;
-use crate::plugins::bullet::BulletSpawnTimer;
-use crate::plugins::cursor_position::CursorPosition;
-use crate::resources::constants::PLAYER_SPEED;
+use crate::components::weapon::{Inventory, Weapon, WeaponKind};
+use crate::plugins::aim::{AimState, AimSystems};
+use crate::plugins::damage::{DamageEvent, Died};
+use crate::plugins::input::ActionState;
+use crate::plugins::pickup::PickupCollected;
+use crate::plugins::weapon::{weapon_sprite, weapon_sprite_transform, WeaponSprite};
+use crate::resources::app_state::AppState;
+use crate::resources::input_bindings::InputAction;
+use crate::resources::constants::{
+    ENEMY_CONTACT_DAMAGE, PLAYER_INVULNERABILITY_SECONDS, PLAYER_MAX_HEALTH,
+    PLAYER_SPEED,
+};
 use crate::setup::camera::MainCamera;
+use crate::systems::cleanup::despawn_with;
 
 #[derive(Component, Deref, Clone, Debug)]
 pub struct Animation(pub benimator::Animation);
@@ -32,61 +47,94 @@ pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(PostStartup, player_setup)
-            .add_systems(Update, player_movement)
-            .add_systems(Update, animate)
-            .add_systems(Update, listen_player_controller)
-            .add_systems(Update, pin_camera_to_player)
-            .add_systems(Update, spawn_bullets_on_pressed);
+        app.add_systems(OnExit(AppState::Loading), player_setup)
+            .add_systems(OnEnter(AppState::MainMenu), despawn_with::<Player>)
+            .add_systems(
+                Update,
+                (
+                    player_movement.after(AimSystems),
+                    damage_player_on_enemy_contact,
+                    animate,
+                    listen_player_controller,
+                    enter_game_over_on_player_death,
+                )
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(Update, pin_camera_to_player);
+    }
+}
+
+fn damage_player_on_enemy_contact(
+    rapier_context: Res<RapierContext>,
+    player_query: Query<(Entity, &Children, &Health, &Invulnerability), With<Player>>,
+    enemy_query: Query<(), With<Enemy>>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for (player_entity, children, health, invulnerability) in player_query.iter() {
+        if invulnerability.is_active() || health.is_dead() {
+            continue;
+        }
+
+        let touching_enemy = children.iter().find_map(|collider| {
+            rapier_context.contacts_with(*collider).find_map(|contact| {
+                let other = if contact.collider1() == *collider {
+                    contact.collider2()
+                } else {
+                    contact.collider1()
+                };
+                (contact.has_any_active_contacts() && enemy_query.contains(other)).then_some(other)
+            })
+        });
+
+        if let Some(enemy) = touching_enemy {
+            damage_events.send(DamageEvent {
+                target: player_entity,
+                amount: ENEMY_CONTACT_DAMAGE,
+                source: Some(enemy),
+                kind: DamageKind::Contact,
+                critical: false,
+            });
+        }
+    }
+}
+
+fn enter_game_over_on_player_death(
+    mut died_events: EventReader<Died>,
+    player_query: Query<(), With<Player>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if died_events.read().any(|event| player_query.contains(event.entity)) {
+        next_state.set(AppState::GameOver);
     }
 }
 
 fn listen_player_controller(
     controllers: Query<(Entity, &KinematicCharacterControllerOutput)>,
     mut commands: Commands,
-    pickups: Query<&Pickup>,
+    pickups: Query<(&Pickup, &Transform, Option<&WeaponPickup>)>,
+    mut pickup_collected_events: EventWriter<PickupCollected>,
+    mut collected: Local<Vec<Entity>>,
 ) {
+    collected.clear();
+
     for (_entity, output) in controllers.iter() {
         if !output.collisions.is_empty() {
             for collision in &output.collisions {
                 let collided_entity = collision.entity;
 
-                if pickups.get(collided_entity).is_ok() {
-                    commands.entity(collided_entity).despawn();
+                if collected.contains(&collided_entity) {
+                    continue;
                 }
-            }
-        }
-    }
-}
 
-fn spawn_bullets_on_pressed(
-    mut commands: Commands,
-    mouse_button_input: Res<Input<MouseButton>>,
-    cursor_position: Res<CursorPosition>,
-    query: Query<&Transform, With<Player>>,
-    mut bullet_spawn_timer: ResMut<BulletSpawnTimer>,
-    time: Res<Time>,
-) {
-    if mouse_button_input.pressed(MouseButton::Left) {
-        if bullet_spawn_timer.0.tick(time.delta()).just_finished() {
-            if let Ok(player_transform) = query.get_single() {
-                let player_position = player_transform.translation.truncate();
-                let bullet_direction = cursor_position.0 - player_position;
-                let bullet_velocity = bullet_direction.normalize_or_zero() * 1000.0;
-                let bullet_angle =
-                    bullet_direction.y.atan2(bullet_direction.x) + std::f32::consts::FRAC_PI_2;
-
-                commands
-                    .spawn(Collider::capsule_y(5., 1.5))
-                    .insert(LockedAxes::ROTATION_LOCKED)
-                    .insert(Bullet {
-                        velocity: bullet_velocity,
-                        lifetime: Timer::from_seconds(1.0, TimerMode::Once),
-                    })
-                    .insert(TransformBundle::from(
-                        Transform::from_xyz(player_position.x, player_position.y, 0.)
-                            .with_rotation(Quat::from_rotation_z(bullet_angle)),
-                    ));
+                if let Ok((pickup, transform, weapon_pickup)) = pickups.get(collided_entity) {
+                    pickup_collected_events.send(PickupCollected {
+                        kind: pickup.kind,
+                        weapon: weapon_pickup.map(|weapon_pickup| weapon_pickup.0),
+                        position: transform.translation.truncate(),
+                    });
+                    collected.push(collided_entity);
+                    commands.entity(collided_entity).despawn();
+                }
             }
         }
     }
@@ -126,6 +174,14 @@ fn player_setup(
                 },
                 ActiveEvents::COLLISION_EVENTS,
             ));
+            children.spawn((
+                SpriteBundle {
+                    sprite: weapon_sprite(WeaponKind::Pistol),
+                    transform: weapon_sprite_transform(WeaponKind::Pistol),
+                    ..default()
+                },
+                WeaponSprite,
+            ));
         })
         .insert(SpriteSheetBundle {
             texture_atlas: texture_atlas_handle,
@@ -135,12 +191,17 @@ fn player_setup(
         .insert(player_animations.idle.clone())
         .insert(player_animations)
         .insert(AnimationState::default())
-        .insert(Player);
+        .insert(Player::default())
+        .insert(Health::new(PLAYER_MAX_HEALTH))
+        .insert(Invulnerability::new(PLAYER_INVULNERABILITY_SECONDS))
+        .insert(Faction::Player)
+        .insert(Buffs::default())
+        .insert(Inventory::new(Weapon::new(WeaponKind::Pistol)));
 }
 
 fn player_movement(
     mut controllers: Query<&mut KinematicCharacterController>,
-    keyboard_input: Res<Input<KeyCode>>,
+    action_state: Res<ActionState>,
     time: Res<Time>,
     mut player_query: Query<
         (
@@ -148,34 +209,43 @@ fn player_movement(
             &mut Animation,
             &PlayerAnimations,
             &mut Transform,
+            &Buffs,
+            &mut Player,
         ),
-        With<Player>,
     >,
-    cursor_position: Res<CursorPosition>,
+    aim: Res<AimState>,
 ) {
     for mut controller in &mut controllers {
-        for (mut state, mut animation, player_animations, mut transform) in player_query.iter_mut()
+        for (mut state, mut animation, player_animations, mut transform, buffs, mut player) in
+            player_query.iter_mut()
         {
             let mut direction = Vec2::ZERO;
             let mut current_animation = PlayerAnimation::Idle;
 
             let directions = [
-                (KeyCode::W, Vec2::new(0.0, 1.0), PlayerAnimation::Running),
-                (KeyCode::A, Vec2::new(-1.0, 0.0), PlayerAnimation::Running),
-                (KeyCode::S, Vec2::new(0.0, -1.0), PlayerAnimation::Running),
-                (KeyCode::D, Vec2::new(1.0, 0.0), PlayerAnimation::Running),
+                (InputAction::MoveUp, Vec2::new(0.0, 1.0), PlayerAnimation::Running),
+                (InputAction::MoveLeft, Vec2::new(-1.0, 0.0), PlayerAnimation::Running),
+                (InputAction::MoveDown, Vec2::new(0.0, -1.0), PlayerAnimation::Running),
+                (InputAction::MoveRight, Vec2::new(1.0, 0.0), PlayerAnimation::Running),
             ];
 
-            for (key, vec, anim) in directions.iter() {
-                if keyboard_input.pressed(*key) {
+            for (action, vec, anim) in directions.iter() {
+                if action_state.pressed(*action) {
                     direction += *vec;
                     current_animation = *anim;
                 }
             }
 
+            // The stick keeps its analog strength, while keys always move at full speed.
+            direction += action_state.move_stick;
+            if action_state.move_stick != Vec2::ZERO {
+                current_animation = PlayerAnimation::Running;
+            }
+
+            player.velocity = direction.clamp_length_max(1.) * PLAYER_SPEED * buffs.multiplier(BuffKind::Speed);
+
             if direction != Vec2::ZERO {
-                direction = direction.normalize();
-                controller.translation = Some(direction * PLAYER_SPEED * time.delta_seconds());
+                controller.translation = Some(player.velocity * time.delta_seconds());
             } else {
                 current_animation = PlayerAnimation::Idle;
             }
@@ -189,9 +259,7 @@ fn player_movement(
                 *animation = new_animation.clone();
             }
 
-            let player_position_vec = transform.translation.truncate();
-            let player_direction_vec = cursor_position.0 - player_position_vec;
-            let angle = player_direction_vec.y.atan2(player_direction_vec.x) + std::f32::consts::PI;
+            let angle = aim.direction.y.atan2(aim.direction.x) + std::f32::consts::PI;
 
             transform.rotation = Quat::from_rotation_z(angle - std::f32::consts::FRAC_PI_2);
 
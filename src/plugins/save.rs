@@ -0,0 +1,266 @@
+use bevy::app::{App, Plugin, Startup, Update};
+use bevy::asset::Assets;
+use bevy::log::warn;
+use bevy::prelude::{
+    in_state, on_event, resource_exists, Commands, Condition, DespawnRecursiveExt, Entity,
+    IntoSystemConfigs, NextState, OnEnter, Query, Res, ResMut, Resource, Transform, With,
+};
+
+use crate::components::enemy::Enemy;
+use crate::components::health::Health;
+use crate::components::pickup::{Pickup, WeaponPickup};
+use crate::components::player::Player;
+use crate::components::spawner::WaveEnemy;
+use crate::components::weapon::{Inventory, Weapon};
+use crate::helpers::save_file::{
+    read_save, save_path, write_save, SaveGame, SavedEnemy, SavedPickup, SavedPlayer, SavedSpawn,
+    SavedWeapon, SAVE_VERSION,
+};
+use crate::plugins::enemy::{spawn_enemy, EnemyArchetypes, EnemyArchetypesHandle, EnemyAtlases};
+use crate::plugins::main_menu::{ContinueGame, MenuPage, SaveAvailable};
+use crate::plugins::pickup::{spawn_pickup, spawn_weapon_pickup};
+use crate::plugins::score::Score;
+use crate::plugins::wave::{QueuedSpawn, WaveCleared, WaveDirector, WaveList, WaveListHandle};
+use crate::resources::app_state::AppState;
+use crate::resources::constants::MAP_PATH;
+
+/// Save read from disk, applied to the freshly spawned world once loading finishes.
+#[derive(Resource)]
+pub struct PendingLoad(pub(crate) SaveGame);
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Startup, check_for_save)
+            .add_systems(
+                Update,
+                continue_saved_game.run_if(in_state(AppState::MainMenu).and_then(on_event::<ContinueGame>())),
+            )
+            .add_systems(OnEnter(AppState::Playing), restore_saved_game.run_if(resource_exists::<PendingLoad>()))
+            // A load that failed on the way back to the menu must not be applied to the next run.
+            .add_systems(OnEnter(AppState::MainMenu), discard_pending_load)
+            // Progress is saved whenever the game is paused and after every cleared wave.
+            .add_systems(OnEnter(AppState::Paused), save_game)
+            .add_systems(
+                Update,
+                save_game.run_if(in_state(AppState::Playing).and_then(on_event::<WaveCleared>())),
+            )
+            .add_systems(OnEnter(AppState::GameOver), delete_save)
+        ;
+    }
+}
+
+fn check_for_save(mut save_available: ResMut<SaveAvailable>) {
+    save_available.0 = save_path().exists();
+}
+
+fn continue_saved_game(
+    mut commands: Commands,
+    mut save_available: ResMut<SaveAvailable>,
+    mut page: ResMut<MenuPage>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let problem = match read_save(&save_path()) {
+        Ok(save) if save.map == MAP_PATH => {
+            commands.insert_resource(PendingLoad(save));
+            next_state.set(AppState::Loading);
+            return;
+        }
+        Ok(save) => format!("it was saved on the unknown map {}", save.map),
+        Err(error) => error.to_string(),
+    };
+
+    warn!("could not load the saved game: {problem}");
+    save_available.0 = false;
+    // Rebuilds the page, now without the Continue button.
+    *page = MenuPage::Main;
+}
+
+fn discard_pending_load(mut commands: Commands) {
+    commands.remove_resource::<PendingLoad>();
+}
+
+fn save_game(
+    score: Res<Score>,
+    director: Res<WaveDirector>,
+    player_query: Query<(&Player, &Transform, &Health, &Inventory)>,
+    enemy_query: Query<(&Enemy, &Transform, &Health, Option<&WaveEnemy>)>,
+    pickup_query: Query<(&Pickup, &Transform, Option<&WeaponPickup>)>,
+    mut save_available: ResMut<SaveAvailable>,
+) {
+    let Ok((player, player_transform, player_health, inventory)) = player_query.get_single() else {
+        return;
+    };
+    if player_health.is_dead() {
+        return;
+    }
+
+    let save = SaveGame {
+        version: SAVE_VERSION,
+        map: MAP_PATH.to_string(),
+        player: SavedPlayer {
+            position: player_transform.translation.truncate().into(),
+            health: player_health.current,
+            zlotowki: player.zlotowki,
+            weapons: inventory
+                .weapons
+                .iter()
+                .map(|weapon| SavedWeapon {
+                    kind: weapon.kind,
+                    magazine: weapon.magazine,
+                    reserve: weapon.reserve,
+                })
+                .collect(),
+            selected_weapon: inventory.selected,
+        },
+        points: score.points,
+        kills: score.kills,
+        waves_started: director.started,
+        spawn_queue: director
+            .spawn_queue()
+            .map(|queued| SavedSpawn {
+                wave: queued.wave,
+                enemy: queued.enemy.clone(),
+                spawner: queued.spawner.clone(),
+            })
+            .collect(),
+        enemies: enemy_query
+            .iter()
+            .filter(|(_, _, health, _)| !health.is_dead())
+            .map(|(enemy, transform, health, wave_enemy)| SavedEnemy {
+                archetype: enemy.archetype.clone(),
+                position: transform.translation.truncate().into(),
+                health: health.current,
+                wave: wave_enemy.map(|wave_enemy| wave_enemy.0),
+            })
+            .collect(),
+        pickups: pickup_query
+            .iter()
+            .map(|(pickup, transform, weapon_pickup)| SavedPickup {
+                kind: pickup.kind,
+                weapon: weapon_pickup.map(|weapon_pickup| weapon_pickup.0),
+                position: transform.translation.truncate().into(),
+            })
+            .collect(),
+    };
+
+    match write_save(&save_path(), &save) {
+        Ok(()) => save_available.0 = true,
+        Err(error) => warn!("could not save the game: {error}"),
+    }
+}
+
+fn restore_saved_game(
+    mut commands: Commands,
+    pending_load: Res<PendingLoad>,
+    archetypes_handle: Res<EnemyArchetypesHandle>,
+    archetypes: Res<Assets<EnemyArchetypes>>,
+    enemy_atlases: Res<EnemyAtlases>,
+    waves_handle: Res<WaveListHandle>,
+    wave_lists: Res<Assets<WaveList>>,
+    mut score: ResMut<Score>,
+    mut director: ResMut<WaveDirector>,
+    mut player_query: Query<(&mut Player, &mut Transform, &mut Health, &mut Inventory)>,
+    pickup_query: Query<Entity, With<Pickup>>,
+) {
+    commands.remove_resource::<PendingLoad>();
+    let save = &pending_load.0;
+
+    if let Ok((mut player, mut transform, mut health, mut inventory)) = player_query.get_single_mut() {
+        let [x, y] = save.player.position;
+        transform.translation.x = x;
+        transform.translation.y = y;
+        health.current = save.player.health.clamp(1, health.max);
+        player.zlotowki = save.player.zlotowki;
+
+        let weapons: Vec<Weapon> = save
+            .player
+            .weapons
+            .iter()
+            .take(Inventory::SLOTS)
+            .map(|saved| {
+                let mut weapon = Weapon::new(saved.kind);
+                weapon.magazine = saved.magazine.min(saved.kind.stats().magazine_size);
+                weapon.reserve = saved.reserve.min(saved.kind.stats().max_reserve);
+                weapon
+            })
+            .collect();
+        if !weapons.is_empty() {
+            inventory.selected = save.player.selected_weapon.min(weapons.len() - 1);
+            inventory.weapons = weapons;
+        }
+    }
+
+    *score = Score {
+        points: save.points,
+        kills: save.kills,
+    };
+
+    for entity in pickup_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for pickup in save.pickups.iter() {
+        let position = pickup.position.into();
+        match pickup.weapon {
+            Some(weapon) => spawn_weapon_pickup(&mut commands, weapon, position),
+            None => spawn_pickup(&mut commands, pickup.kind, position),
+        }
+    }
+
+    let mut active_waves = Vec::new();
+    if let Some(archetypes) = archetypes.get(&archetypes_handle.0) {
+        for saved in save.enemies.iter() {
+            let Some(archetype) = archetypes.0.get(&saved.archetype) else {
+                continue;
+            };
+
+            let position = saved.position.into();
+            let enemy = spawn_enemy(&mut commands, &saved.archetype, archetype, &enemy_atlases, position);
+            let mut health = Health::new(archetype.health);
+            health.current = saved.health.clamp(1, health.max);
+            commands.entity(enemy).insert(health);
+
+            if let Some(wave) = saved.wave {
+                commands.entity(enemy).insert(WaveEnemy(wave));
+                if !active_waves.contains(&wave) {
+                    active_waves.push(wave);
+                }
+            }
+        }
+    }
+
+    let spawn_queue: Vec<QueuedSpawn> = save
+        .spawn_queue
+        .iter()
+        .map(|saved| QueuedSpawn {
+            wave: saved.wave,
+            enemy: saved.enemy.clone(),
+            spawner: saved.spawner.clone(),
+        })
+        .collect();
+    for queued in spawn_queue.iter() {
+        if !active_waves.contains(&queued.wave) {
+            active_waves.push(queued.wave);
+        }
+    }
+    // Queued enemies keep spawning at the pace of the latest wave, as they did before saving.
+    let spawn_interval = wave_lists
+        .get(&waves_handle.0)
+        .zip(save.waves_started.checked_sub(1))
+        .and_then(|(wave_list, latest)| wave_list.waves.get(latest))
+        .map_or(0., |wave| wave.spawn_interval);
+
+    *director = WaveDirector::resumed(save.waves_started, active_waves, spawn_queue, spawn_interval);
+}
+
+fn delete_save(mut save_available: ResMut<SaveAvailable>) {
+    let path = save_path();
+    if path.exists() {
+        if let Err(error) = std::fs::remove_file(&path) {
+            warn!("could not delete the saved game: {error}");
+        }
+    }
+    save_available.0 = false;
+}
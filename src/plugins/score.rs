@@ -0,0 +1,52 @@
+use bevy::app::{App, Plugin, Update};
+use bevy::asset::Assets;
+use bevy::prelude::{
+    in_state, Commands, EventReader, IntoSystemConfigs, OnExit, Query, Res, ResMut, Resource, With,
+};
+
+use crate::components::player::Player;
+use crate::plugins::enemy::{EnemyArchetypes, EnemyArchetypesHandle, EnemyDied};
+use crate::resources::app_state::AppState;
+
+/// Score of the current run.
+#[derive(Resource, Default)]
+pub struct Score {
+    pub(crate) points: u32,
+    pub(crate) kills: u32,
+}
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Score>()
+            .add_systems(OnExit(AppState::Loading), reset_score)
+            .add_systems(Update, award_score.run_if(in_state(AppState::Playing)))
+        ;
+    }
+}
+
+fn reset_score(mut commands: Commands) {
+    commands.insert_resource(Score::default());
+}
+
+fn award_score(
+    mut enemy_died_events: EventReader<EnemyDied>,
+    archetypes_handle: Res<EnemyArchetypesHandle>,
+    archetypes: Res<Assets<EnemyArchetypes>>,
+    player_query: Query<(), With<Player>>,
+    mut score: ResMut<Score>,
+) {
+    for event in enemy_died_events.read() {
+        if !event.killer.map_or(false, |killer| player_query.contains(killer)) {
+            continue;
+        }
+
+        score.kills += 1;
+        score.points += archetypes
+            .get(&archetypes_handle.0)
+            .and_then(|archetypes| archetypes.0.get(&event.archetype))
+            .map_or(0, |archetype| archetype.score);
+    }
+}
@@ -0,0 +1,232 @@
+use bevy::audio::{AudioSinkPlayback, GlobalVolume, Volume, VolumeLevel};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_rapier2d::render::DebugRenderContext;
+
+use crate::components::audio::AudioChannel;
+use crate::plugins::main_menu::{menu_button, MenuAction, MenuActivated, MenuButton, MenuStepped};
+use crate::resources::locale::Locale;
+use crate::resources::settings::{DisplayMode, Language, Settings, RESOLUTIONS};
+
+const VOLUME_STEP: f32 = 0.1;
+const SENSITIVITY_STEP: f32 = 0.1;
+const MIN_SENSITIVITY: f32 = 0.1;
+const MAX_SENSITIVITY: f32 = 3.;
+
+/// One row of the settings menu.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SettingKind {
+    Resolution,
+    DisplayMode,
+    Vsync,
+    MasterVolume,
+    MusicVolume,
+    EffectsVolume,
+    MouseSensitivity,
+    PhysicsDebug,
+    Language,
+}
+
+impl SettingKind {
+    pub const ALL: [SettingKind; 9] = [
+        SettingKind::Resolution,
+        SettingKind::DisplayMode,
+        SettingKind::Vsync,
+        SettingKind::MasterVolume,
+        SettingKind::MusicVolume,
+        SettingKind::EffectsVolume,
+        SettingKind::MouseSensitivity,
+        SettingKind::PhysicsDebug,
+        SettingKind::Language,
+    ];
+
+    pub fn label(&self, settings: &Settings, locale: &Locale) -> String {
+        let on_off = |value: bool| locale.tr(if value { "On" } else { "Off" });
+        let percent = |value: f32| format!("{:.0}%", value * 100.);
+
+        match self {
+            SettingKind::Resolution => {
+                format!("{}: {}x{}", locale.tr("Resolution"), settings.resolution[0], settings.resolution[1])
+            }
+            SettingKind::DisplayMode => {
+                format!("{}: {}", locale.tr("Display"), locale.tr(settings.display_mode.name()))
+            }
+            SettingKind::Vsync => format!("VSync: {}", on_off(settings.vsync)),
+            SettingKind::MasterVolume => {
+                format!("{}: {}", locale.tr("Master volume"), percent(settings.master_volume))
+            }
+            SettingKind::MusicVolume => {
+                format!("{}: {}", locale.tr("Music volume"), percent(settings.music_volume))
+            }
+            SettingKind::EffectsVolume => {
+                format!("{}: {}", locale.tr("Effects volume"), percent(settings.effects_volume))
+            }
+            SettingKind::MouseSensitivity => {
+                format!("{}: {:.1}", locale.tr("Mouse sensitivity"), settings.mouse_sensitivity)
+            }
+            SettingKind::PhysicsDebug => {
+                format!("{}: {}", locale.tr("Physics debug"), on_off(settings.show_physics_debug))
+            }
+            SettingKind::Language => format!("{}: {}", locale.tr("Language"), settings.language.name()),
+        }
+    }
+
+    /// Moves the setting `step` notches; lists wrap around, numbers are clamped.
+    pub fn step(&self, settings: &mut Settings, step: i32) {
+        match self {
+            SettingKind::Resolution => {
+                let current = RESOLUTIONS.iter().position(|resolution| *resolution == settings.resolution);
+                settings.resolution = RESOLUTIONS[cycle(current.unwrap_or(0), RESOLUTIONS.len(), step)];
+            }
+            SettingKind::DisplayMode => {
+                let current = DisplayMode::ALL.iter().position(|mode| *mode == settings.display_mode);
+                settings.display_mode = DisplayMode::ALL[cycle(current.unwrap_or(0), DisplayMode::ALL.len(), step)];
+            }
+            SettingKind::Vsync => settings.vsync = !settings.vsync,
+            SettingKind::MasterVolume => step_volume(&mut settings.master_volume, step),
+            SettingKind::MusicVolume => step_volume(&mut settings.music_volume, step),
+            SettingKind::EffectsVolume => step_volume(&mut settings.effects_volume, step),
+            SettingKind::MouseSensitivity => {
+                settings.mouse_sensitivity = (settings.mouse_sensitivity + step as f32 * SENSITIVITY_STEP)
+                    .clamp(MIN_SENSITIVITY, MAX_SENSITIVITY);
+            }
+            SettingKind::PhysicsDebug => settings.show_physics_debug = !settings.show_physics_debug,
+            SettingKind::Language => {
+                let current = Language::ALL.iter().position(|language| *language == settings.language);
+                settings.language = Language::ALL[cycle(current.unwrap_or(0), Language::ALL.len(), step)];
+            }
+        }
+    }
+}
+
+fn cycle(index: usize, len: usize, step: i32) -> usize {
+    (index as i32 + step).rem_euclid(len as i32) as usize
+}
+
+fn step_volume(volume: &mut f32, step: i32) {
+    // Rounded so repeated steps do not drift away from whole percentages.
+    *volume = ((*volume + step as f32 * VOLUME_STEP) * 100.).round().clamp(0., 100.) / 100.;
+}
+
+/// Volume a sink plays at: its own relative volume scaled by the master volume and by the volume
+/// of its channel. Absolute volumes ignore the settings, like they ignore `GlobalVolume`.
+fn sink_volume(settings: &Settings, playback: &PlaybackSettings, channel: Option<&AudioChannel>) -> f32 {
+    let channel_volume = match channel {
+        Some(AudioChannel::Music) => settings.music_volume,
+        Some(AudioChannel::Effects) => settings.effects_volume,
+        None => 1.,
+    };
+
+    match playback.volume {
+        Volume::Relative(level) => level.get() * settings.master_volume * channel_volume,
+        Volume::Absolute(level) => level.get(),
+    }
+}
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Locale>()
+            .add_systems(
+                Update,
+                (
+                    change_settings,
+                    (apply_settings, update_setting_labels).run_if(resource_changed::<Settings>()),
+                    // Inserting the settings read from disk counts as a change, but needs no writing back.
+                    save_settings
+                        .run_if(resource_changed::<Settings>().and_then(not(resource_added::<Settings>()))),
+                    apply_channel_volume,
+                )
+                    .chain(),
+            )
+        ;
+    }
+}
+
+/// Adds a button per setting, numbered from `first_index` in the menu focus order.
+pub fn spawn_setting_buttons(parent: &mut ChildBuilder, settings: &Settings, locale: &Locale, first_index: usize) {
+    for (offset, kind) in SettingKind::ALL.into_iter().enumerate() {
+        menu_button(parent, &kind.label(settings, locale), MenuAction::Setting(kind), first_index + offset);
+    }
+}
+
+/// Activating a setting steps it forward, left and right step it either way.
+fn change_settings(
+    mut menu_activated_events: EventReader<MenuActivated>,
+    mut menu_stepped_events: EventReader<MenuStepped>,
+    mut settings: ResMut<Settings>,
+) {
+    let activated = menu_activated_events.read().map(|event| (event.0, 1));
+    let stepped = menu_stepped_events.read().map(|event| (event.action, event.step));
+
+    for (action, step) in activated.chain(stepped) {
+        if let MenuAction::Setting(kind) = action {
+            kind.step(&mut settings, step);
+        }
+    }
+}
+
+fn apply_settings(
+    settings: Res<Settings>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+    mut global_volume: ResMut<GlobalVolume>,
+    sink_query: Query<(&AudioSink, &PlaybackSettings, Option<&AudioChannel>)>,
+    mut debug_render_context: ResMut<DebugRenderContext>,
+    mut locale: ResMut<Locale>,
+) {
+    if let Ok(mut window) = window_query.get_single_mut() {
+        let [width, height] = settings.resolution;
+        if window.resolution.width() != width as f32 || window.resolution.height() != height as f32 {
+            window.resolution.set(width as f32, height as f32);
+        }
+        window.mode = settings.display_mode.window_mode();
+        window.present_mode = settings.present_mode();
+    }
+
+    // The global volume only applies to sounds started from now on, so playing ones are adjusted too.
+    global_volume.volume = VolumeLevel::new(settings.master_volume);
+    for (sink, playback, channel) in sink_query.iter() {
+        sink.set_volume(sink_volume(&settings, playback, channel));
+    }
+    debug_render_context.enabled = settings.show_physics_debug;
+    // Compared first, so text depending on the locale is only rebuilt when the language changed.
+    if locale.0 != settings.language {
+        locale.0 = settings.language;
+    }
+}
+
+/// Sounds start at the master volume only, so music and effects get their channel volume once
+/// their sink exists, a frame after they were spawned.
+fn apply_channel_volume(
+    settings: Res<Settings>,
+    sink_query: Query<(&AudioSink, &PlaybackSettings, &AudioChannel), Added<AudioSink>>,
+) {
+    for (sink, playback, channel) in sink_query.iter() {
+        sink.set_volume(sink_volume(&settings, playback, Some(channel)));
+    }
+}
+
+fn save_settings(settings: Res<Settings>) {
+    settings.save();
+}
+
+fn update_setting_labels(
+    settings: Res<Settings>,
+    locale: Res<Locale>,
+    button_query: Query<(&MenuButton, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (button, children) in button_query.iter() {
+        let MenuAction::Setting(kind) = button.action else {
+            continue;
+        };
+
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = kind.label(&settings, &locale);
+            }
+        }
+    }
+}
@@ -1,37 +1,96 @@
-use bevy::app::{App, Plugin, Startup};
+use bevy::app::{App, Plugin, Update};
+use bevy::asset::Assets;
 use bevy::core::Name;
-use bevy::prelude::{Commands, Transform, TransformBundle};
+use bevy::math::Quat;
+use bevy::prelude::{Commands, DespawnRecursiveExt, Entity, EventReader, Query, Res, Transform, TransformBundle, With};
 use bevy_rapier2d::dynamics::RigidBody;
-use bevy_rapier2d::geometry::{ActiveEvents, Collider, Sensor};
+use bevy_rapier2d::geometry::{ActiveEvents, Collider};
 
 use crate::components::wall::Wall;
+use crate::helpers::map_loader::TiledMap;
+use crate::helpers::navigation::{NavGrid, Obstacle};
+use crate::plugins::map::{CurrentMap, MapLoaded};
+use crate::resources::constants::{COLLISION_LAYER, MAP_SCALE, NAV_CELL_SIZE, NAV_CLEARANCE};
 
 pub struct WallPlugin;
 
 impl Plugin for WallPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_systems(Startup, spawn_wall)
+            .add_systems(Update, spawn_walls)
         ;
     }
 }
 
+fn spawn_walls(
+    mut commands: Commands,
+    mut map_loaded_events: EventReader<MapLoaded>,
+    current_map: Res<CurrentMap>,
+    maps: Res<Assets<TiledMap>>,
+    wall_query: Query<Entity, With<Wall>>,
+) {
+    if map_loaded_events.read().count() == 0 {
+        return;
+    }
+
+    let Some(tiled_map) = maps.get(&current_map.0) else {
+        return;
+    };
+    let map = &tiled_map.map;
+
+    for entity in wall_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let map_size = map.pixel_size() * MAP_SCALE;
+    let mut nav_grid = NavGrid::new(-map_size / 2., map_size, NAV_CELL_SIZE);
+
+    for layer in map.layers.iter().filter(|layer| layer.name == COLLISION_LAYER) {
+        if layer.is_tile_layer() {
+            for rect in layer.solid_rects() {
+                let (center, half_extents) = map.cell_rect_bounds(&rect);
+                let obstacle = Obstacle {
+                    center: center * MAP_SCALE,
+                    half_extents: half_extents * MAP_SCALE,
+                    rotation: 0.,
+                };
+                spawn_wall(&mut commands, "wall", &obstacle);
+                nav_grid.block_obstacle(&obstacle, NAV_CLEARANCE);
+            }
+        } else if layer.is_object_group() {
+            for object in layer.objects.iter().filter(|object| object.width > 0. && object.height > 0.) {
+                let (center, half_extents, rotation) = map.object_bounds(object);
+                let obstacle = Obstacle {
+                    center: center * MAP_SCALE,
+                    half_extents: half_extents * MAP_SCALE,
+                    rotation,
+                };
+                let name = if object.name.is_empty() { "wall" } else { &object.name };
+                spawn_wall(&mut commands, name, &obstacle);
+                nav_grid.block_obstacle(&obstacle, NAV_CLEARANCE);
+            }
+        }
+    }
+
+    commands.insert_resource(nav_grid);
+}
+
 fn spawn_wall(
-    mut commands: Commands
+    commands: &mut Commands,
+    name: &str,
+    obstacle: &Obstacle,
 ) {
-    /*
-    * @TODO: Wall a bit hacked by rapier collisions, but working properly?
-    *   It's not. Enemy has an ability to "walk" through the wall.
-    */
     commands
         .spawn((
-            TransformBundle::from(Transform::from_xyz(-150., 10., 0.)),
-            Collider::cuboid(50., 1500.),
-            RigidBody::Dynamic,
+            TransformBundle::from(
+                Transform::from_xyz(obstacle.center.x, obstacle.center.y, 0.)
+                    .with_rotation(Quat::from_rotation_z(obstacle.rotation)),
+            ),
+            Collider::cuboid(obstacle.half_extents.x, obstacle.half_extents.y),
+            RigidBody::Fixed,
             ActiveEvents::COLLISION_EVENTS,
-            Sensor
         ))
         .insert(Wall)
-        .insert(Name::new("wall"))
+        .insert(Name::new(name.to_string()))
     ;
 }
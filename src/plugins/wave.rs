@@ -0,0 +1,415 @@
+use std::collections::VecDeque;
+
+use bevy::app::{App, Plugin, Startup, Update};
+use bevy::asset::{Asset, AssetApp, AssetServer, Assets, Handle};
+use bevy::core::Name;
+use bevy::math::{Quat, Vec2};
+use bevy::prelude::{
+    apply_deferred, in_state, Commands, DespawnRecursiveExt, Entity, Event, EventReader, EventWriter,
+    IntoSystemConfigs, OnExit, Query, Res, ResMut, Resource, Time, Timer, TimerMode, Transform,
+    TransformBundle, With, warn,
+};
+use bevy::reflect::TypePath;
+use bevy::time::Stopwatch;
+use rand::seq::SliceRandom;
+use rand::{Rng, thread_rng};
+use serde::Deserialize;
+
+use crate::components::enemy::Enemy;
+use crate::components::spawner::{Spawner, WaveEnemy};
+use crate::helpers::json_asset::JsonAssetLoader;
+use crate::helpers::map_loader::{Map, TiledMap};
+use crate::plugins::enemy::{spawn_enemy, EnemyArchetypes, EnemyArchetypesHandle, EnemyAtlases};
+use crate::plugins::game_state::LoadingAssets;
+use crate::plugins::map::{CurrentMap, MapLoaded};
+use crate::resources::app_state::AppState;
+use crate::resources::constants::{MAP_SCALE, SPAWNER_LAYER};
+
+/// When a wave may start, checked once the previous wave has started.
+#[derive(Deserialize, Clone, Copy)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WaveStart {
+    /// Every enemy of the earlier waves is dead.
+    Cleared,
+    /// This many seconds passed since the previous wave (or the run) started.
+    Elapsed { seconds: f32 },
+    /// Fewer than `count` enemies are alive.
+    AliveBelow { count: usize },
+}
+
+#[derive(Deserialize, Clone)]
+pub struct EnemyGroup {
+    pub(crate) enemy: String,
+    pub(crate) count: u32,
+    /// Name of the spawner to use, any spawner when missing.
+    #[serde(default)]
+    pub(crate) spawner: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Wave {
+    pub(crate) start: WaveStart,
+    /// Seconds between the start condition being met and the first spawn.
+    #[serde(default)]
+    pub(crate) delay: f32,
+    /// Seconds between two spawns.
+    pub(crate) spawn_interval: f32,
+    pub(crate) groups: Vec<EnemyGroup>,
+}
+
+#[derive(Asset, TypePath, Deserialize)]
+pub struct WaveList {
+    pub(crate) waves: Vec<Wave>,
+}
+
+impl WaveList {
+    /// Spawner names used by the waves that `map` does not have, with the number of the wave using them.
+    pub(crate) fn unknown_spawners<'a>(&'a self, map: &Map) -> Vec<(usize, &'a str)> {
+        let known: Vec<&str> = map
+            .layers
+            .iter()
+            .filter(|layer| layer.name == SPAWNER_LAYER && layer.is_object_group())
+            .flat_map(|layer| layer.objects.iter().map(|object| object.name.as_str()))
+            .collect();
+
+        self.waves
+            .iter()
+            .enumerate()
+            .flat_map(|(index, wave)| {
+                wave.groups
+                    .iter()
+                    .filter_map(|group| group.spawner.as_deref())
+                    .map(move |name| (index + 1, name))
+            })
+            .filter(|(_, name)| !known.contains(name))
+            .collect()
+    }
+}
+
+#[derive(Resource)]
+pub struct WaveListHandle(pub(crate) Handle<WaveList>);
+
+/// Waves are numbered from one in events.
+#[derive(Event)]
+pub struct WaveStarted {
+    pub(crate) wave: usize,
+}
+
+#[derive(Event)]
+pub struct WaveCleared {
+    pub(crate) wave: usize,
+}
+
+/// Enemy of a started wave waiting for its turn to spawn.
+pub struct QueuedSpawn {
+    pub(crate) wave: usize,
+    pub(crate) enemy: String,
+    pub(crate) spawner: Option<String>,
+}
+
+/// Progress of the current run through the wave list.
+#[derive(Resource)]
+pub struct WaveDirector {
+    /// Number of waves started so far, so also the number of the current wave.
+    pub(crate) started: usize,
+    since_last_start: Stopwatch,
+    start_delay: Option<Timer>,
+    spawn_timer: Timer,
+    spawn_queue: VecDeque<QueuedSpawn>,
+    /// Waves that started but still have enemies queued or alive.
+    active: Vec<usize>,
+}
+
+impl Default for WaveDirector {
+    fn default() -> Self {
+        WaveDirector {
+            started: 0,
+            since_last_start: Stopwatch::new(),
+            start_delay: None,
+            spawn_timer: Timer::from_seconds(0., TimerMode::Repeating),
+            spawn_queue: VecDeque::new(),
+            active: Vec::new(),
+        }
+    }
+}
+
+impl WaveDirector {
+    /// Director for a run restored from a save, with `started` waves behind it, the given waves
+    /// still having enemies alive or queued, and `spawn_queue` spawning every `spawn_interval` seconds.
+    pub(crate) fn resumed(
+        started: usize,
+        active: Vec<usize>,
+        spawn_queue: Vec<QueuedSpawn>,
+        spawn_interval: f32,
+    ) -> Self {
+        WaveDirector {
+            started,
+            active,
+            spawn_timer: Timer::from_seconds(spawn_interval, TimerMode::Repeating),
+            spawn_queue: spawn_queue.into(),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn spawn_queue(&self) -> impl Iterator<Item = &QueuedSpawn> {
+        self.spawn_queue.iter()
+    }
+
+    /// Whether every wave started so far has been cleared.
+    pub(crate) fn is_clear(&self) -> bool {
+        self.active.is_empty()
+    }
+}
+
+pub struct WavePlugin;
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_asset::<WaveList>()
+            .register_asset_loader(JsonAssetLoader::<WaveList>::new(&["waves.json"]))
+            .init_resource::<WaveDirector>()
+            .add_event::<WaveStarted>()
+            .add_event::<WaveCleared>()
+            .add_systems(Startup, load_waves)
+            .add_systems(Update, spawn_spawners)
+            .add_systems(OnExit(AppState::Loading), (reset_wave_director, check_wave_spawners))
+            // The enemy spawned this frame has to exist before its wave can be checked for being cleared.
+            .add_systems(
+                Update,
+                (start_waves, spawn_queued_enemies, apply_deferred, clear_waves)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            )
+        ;
+    }
+}
+
+fn load_waves(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading_assets: ResMut<LoadingAssets>,
+) {
+    let waves = asset_server.load("list.waves.json");
+    loading_assets.0.push(waves.clone().untyped());
+    commands.insert_resource(WaveListHandle(waves));
+}
+
+fn spawn_spawners(
+    mut commands: Commands,
+    mut map_loaded_events: EventReader<MapLoaded>,
+    current_map: Res<CurrentMap>,
+    maps: Res<Assets<TiledMap>>,
+    spawner_query: Query<Entity, With<Spawner>>,
+) {
+    if map_loaded_events.read().count() == 0 {
+        return;
+    }
+
+    let Some(tiled_map) = maps.get(&current_map.0) else {
+        return;
+    };
+    let map = &tiled_map.map;
+
+    for entity in spawner_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    for layer in map.layers.iter().filter(|layer| layer.name == SPAWNER_LAYER && layer.is_object_group()) {
+        for object in layer.objects.iter() {
+            let (center, half_extents, rotation) = map.object_bounds(object);
+            let center = center * MAP_SCALE;
+
+            commands
+                .spawn(TransformBundle::from(
+                    Transform::from_xyz(center.x, center.y, 0.).with_rotation(Quat::from_rotation_z(rotation)),
+                ))
+                .insert(Spawner {
+                    name: object.name.clone(),
+                    half_extents: half_extents * MAP_SCALE,
+                })
+                .insert(Name::new(format!("spawner {}", object.name)))
+            ;
+        }
+    }
+}
+
+fn reset_wave_director(mut commands: Commands) {
+    commands.insert_resource(WaveDirector::default());
+}
+
+fn check_wave_spawners(
+    current_map: Res<CurrentMap>,
+    maps: Res<Assets<TiledMap>>,
+    waves_handle: Res<WaveListHandle>,
+    wave_lists: Res<Assets<WaveList>>,
+) {
+    let (Some(tiled_map), Some(wave_list)) = (maps.get(&current_map.0), wave_lists.get(&waves_handle.0)) else {
+        return;
+    };
+
+    for (wave, name) in wave_list.unknown_spawners(&tiled_map.map) {
+        warn!("wave {wave} uses unknown spawner {name}, its enemies will use random spawners");
+    }
+}
+
+fn start_waves(
+    time: Res<Time>,
+    waves_handle: Res<WaveListHandle>,
+    wave_lists: Res<Assets<WaveList>>,
+    mut director: ResMut<WaveDirector>,
+    enemy_query: Query<(), With<Enemy>>,
+    mut wave_started_events: EventWriter<WaveStarted>,
+) {
+    let Some(wave_list) = wave_lists.get(&waves_handle.0) else {
+        return;
+    };
+    let Some(wave) = wave_list.waves.get(director.started) else {
+        return;
+    };
+
+    director.since_last_start.tick(time.delta());
+
+    if director.start_delay.is_none() {
+        let ready = match wave.start {
+            WaveStart::Cleared => director.active.is_empty(),
+            WaveStart::Elapsed { seconds } => director.since_last_start.elapsed_secs() >= seconds,
+            WaveStart::AliveBelow { count } => enemy_query.iter().count() < count,
+        };
+
+        if ready {
+            director.start_delay = Some(Timer::from_seconds(wave.delay, TimerMode::Once));
+        }
+    }
+
+    let Some(start_delay) = director.start_delay.as_mut() else {
+        return;
+    };
+    if !start_delay.tick(time.delta()).finished() {
+        return;
+    }
+
+    let index = director.started;
+    director.start_delay = None;
+    director.started += 1;
+    director.since_last_start.reset();
+    director.active.push(index);
+    director.spawn_timer = Timer::from_seconds(wave.spawn_interval, TimerMode::Repeating);
+    // Let the first enemy of the wave appear on the next tick.
+    let interval = director.spawn_timer.duration();
+    director.spawn_timer.set_elapsed(interval);
+
+    for group in wave.groups.iter() {
+        for _ in 0..group.count {
+            director.spawn_queue.push_back(QueuedSpawn {
+                wave: index,
+                enemy: group.enemy.clone(),
+                spawner: group.spawner.clone(),
+            });
+        }
+    }
+
+    wave_started_events.send(WaveStarted { wave: index + 1 });
+}
+
+fn spawn_queued_enemies(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut director: ResMut<WaveDirector>,
+    archetypes_handle: Res<EnemyArchetypesHandle>,
+    archetypes: Res<Assets<EnemyArchetypes>>,
+    enemy_atlases: Res<EnemyAtlases>,
+    spawner_query: Query<(&Spawner, &Transform)>,
+) {
+    let Some(archetypes) = archetypes.get(&archetypes_handle.0) else {
+        return;
+    };
+    if director.spawn_queue.is_empty() {
+        return;
+    }
+
+    if !director.spawn_timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let Some(queued) = director.spawn_queue.front() else {
+        return;
+    };
+
+    let spawners: Vec<_> = spawner_query.iter().collect();
+    let named = queued
+        .spawner
+        .as_ref()
+        .and_then(|name| spawners.iter().find(|(spawner, _)| &spawner.name == name));
+    let mut rng = thread_rng();
+
+    // Unknown spawner names fall back to a random spawner rather than dropping the enemy.
+    // Without any spawner the enemy stays queued until the map provides one.
+    let Some((spawner, spawner_transform)) = named.or_else(|| spawners.choose(&mut rng)) else {
+        return;
+    };
+    let Some(archetype) = archetypes.0.get(&queued.enemy) else {
+        // It can never spawn, so skip it instead of holding up the rest of the queue.
+        warn!("wave {} queues unknown enemy {}, skipping it", queued.wave + 1, queued.enemy);
+        director.spawn_queue.pop_front();
+        return;
+    };
+
+    let offset = Vec2::new(
+        rng.gen_range(-spawner.half_extents.x..=spawner.half_extents.x),
+        rng.gen_range(-spawner.half_extents.y..=spawner.half_extents.y),
+    );
+    let position = spawner_transform.transform_point(offset.extend(0.)).truncate();
+
+    let enemy = spawn_enemy(&mut commands, &queued.enemy, archetype, &enemy_atlases, position);
+    commands.entity(enemy).insert(WaveEnemy(queued.wave));
+    director.spawn_queue.pop_front();
+}
+
+fn clear_waves(
+    mut director: ResMut<WaveDirector>,
+    wave_enemy_query: Query<&WaveEnemy>,
+    mut wave_cleared_events: EventWriter<WaveCleared>,
+) {
+    let director = &mut *director;
+    let spawn_queue = &director.spawn_queue;
+
+    director.active.retain(|wave| {
+        let cleared = !spawn_queue.iter().any(|queued| queued.wave == *wave)
+            && !wave_enemy_query.iter().any(|wave_enemy| wave_enemy.0 == *wave);
+
+        if cleared {
+            wave_cleared_events.send(WaveCleared { wave: wave + 1 });
+        }
+        !cleared
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::helpers::map_loader::parse_map;
+
+    use super::*;
+
+    #[test]
+    fn waves_only_use_spawners_of_the_map() {
+        let map = parse_map(include_bytes!("../../assets/stare-bielany.tmj")).unwrap();
+        let wave_list: WaveList = serde_json::from_slice(include_bytes!("../../assets/list.waves.json")).unwrap();
+
+        assert_eq!(wave_list.unknown_spawners(&map), Vec::new());
+    }
+
+    #[test]
+    fn misnamed_spawners_are_reported_with_their_wave() {
+        let map = parse_map(include_bytes!("../../assets/stare-bielany.tmj")).unwrap();
+        let wave_list: WaveList = serde_json::from_str(
+            r#"{ "waves": [
+                { "start": { "type": "cleared" }, "spawn_interval": 1, "groups": [{ "enemy": "dog", "count": 1, "spawner": "north" }] },
+                { "start": { "type": "cleared" }, "spawn_interval": 1, "groups": [{ "enemy": "dog", "count": 1, "spawner": "nowhere" }] }
+            ] }"#,
+        )
+        .unwrap();
+
+        assert_eq!(wave_list.unknown_spawners(&map), vec![(2, "nowhere")]);
+    }
+}
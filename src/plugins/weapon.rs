@@ -0,0 +1,196 @@
+use bevy::prelude::*;
+use rand::{Rng, thread_rng};
+
+use crate::components::buff::{BuffKind, Buffs};
+use crate::components::bullet::Bullet;
+use crate::components::faction::Faction;
+use crate::components::player::Player;
+use crate::components::weapon::{Inventory, WeaponKind};
+use crate::plugins::bullet::spawn_bullet;
+use crate::plugins::aim::{AimState, AimSystems};
+use crate::plugins::input::ActionState;
+use crate::resources::app_state::AppState;
+use crate::resources::input_bindings::InputAction;
+use crate::resources::constants::CRIT_MULTIPLIER;
+
+#[derive(Component)]
+pub struct WeaponSprite;
+
+/// Sent for every shot fired, so anything within earshot can react.
+#[derive(Event)]
+pub struct Gunshot {
+    pub(crate) position: Vec2,
+}
+
+pub struct WeaponPlugin;
+
+impl Plugin for WeaponPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Gunshot>()
+            .add_systems(
+                Update,
+                (
+                    switch_weapon_on_pressed,
+                    reload_on_pressed,
+                    tick_reload,
+                    spawn_bullets_on_pressed,
+                    update_weapon_sprite,
+                )
+                    .chain()
+                    .after(AimSystems)
+                    .run_if(in_state(AppState::Playing)),
+            );
+    }
+}
+
+pub fn weapon_sprite(kind: WeaponKind) -> Sprite {
+    let size = kind.sprite_size();
+
+    Sprite {
+        color: kind.color(),
+        custom_size: Some(Vec2::new(size.y, size.x)),
+        ..default()
+    }
+}
+
+/// The player sprite faces its local -Y axis, so the weapon sticks out that way from the right hand.
+pub fn weapon_sprite_transform(kind: WeaponKind) -> Transform {
+    Transform::from_xyz(10., -20. - kind.sprite_size().x / 2., 0.1)
+}
+
+fn switch_weapon_on_pressed(
+    action_state: Res<ActionState>,
+    mut query: Query<&mut Inventory, With<Player>>,
+) {
+    for mut inventory in query.iter_mut() {
+        let slot = InputAction::WEAPON_SLOTS
+            .iter()
+            .position(|action| action_state.just_pressed(*action));
+
+        if let Some(slot) = slot {
+            inventory.select(slot);
+        } else if action_state.just_pressed(InputAction::NextWeapon) {
+            inventory.cycle(1);
+        } else if action_state.just_pressed(InputAction::PreviousWeapon) {
+            inventory.cycle(-1);
+        }
+    }
+}
+
+fn reload_on_pressed(
+    action_state: Res<ActionState>,
+    mut query: Query<&mut Inventory, With<Player>>,
+) {
+    if action_state.just_pressed(InputAction::Reload) {
+        for mut inventory in query.iter_mut() {
+            inventory.current_mut().start_reload();
+        }
+    }
+}
+
+fn tick_reload(
+    time: Res<Time>,
+    mut query: Query<&mut Inventory>,
+) {
+    for mut inventory in query.iter_mut() {
+        let weapon = inventory.current_mut();
+        let finished = match weapon.reload.as_mut() {
+            Some(reload) => reload.tick(time.delta()).finished(),
+            None => false,
+        };
+
+        if finished {
+            weapon.finish_reload();
+        }
+    }
+}
+
+fn update_weapon_sprite(
+    inventory_query: Query<(&Inventory, &Children), Changed<Inventory>>,
+    mut sprite_query: Query<(&mut Sprite, &mut Transform), With<WeaponSprite>>,
+) {
+    for (inventory, children) in inventory_query.iter() {
+        let kind = inventory.current().kind;
+
+        for child in children.iter() {
+            if let Ok((mut sprite, mut transform)) = sprite_query.get_mut(*child) {
+                let wanted = weapon_sprite(kind);
+
+                if sprite.custom_size != wanted.custom_size || sprite.color != wanted.color {
+                    *sprite = wanted;
+                    *transform = weapon_sprite_transform(kind);
+                }
+            }
+        }
+    }
+}
+
+fn spawn_bullets_on_pressed(
+    mut commands: Commands,
+    action_state: Res<ActionState>,
+    aim: Res<AimState>,
+    mut query: Query<(Entity, &Transform, &Buffs, &mut Inventory), With<Player>>,
+    mut gunshot_events: EventWriter<Gunshot>,
+    time: Res<Time>,
+) {
+    if let Ok((player_entity, player_transform, buffs, mut inventory)) = query.get_single_mut() {
+        let weapon = inventory.current_mut();
+        weapon.cooldown.tick(time.delta());
+
+        let stats = weapon.kind.stats();
+        let trigger_pulled = if stats.automatic {
+            action_state.pressed(InputAction::Fire)
+        } else {
+            action_state.just_pressed(InputAction::Fire)
+        };
+
+        if !trigger_pulled || !weapon.cooldown.finished() || weapon.is_reloading() {
+            return;
+        }
+
+        if weapon.magazine == 0 {
+            weapon.start_reload();
+            return;
+        }
+
+        weapon.magazine -= 1;
+        weapon.cooldown.reset();
+
+        let player_position = player_transform.translation.truncate();
+        let damage = (stats.damage as f32 * buffs.multiplier(BuffKind::Damage)).round() as i32;
+        let mut rng = thread_rng();
+
+        gunshot_events.send(Gunshot {
+            position: player_position,
+        });
+
+        for _ in 0..stats.pellets {
+            let deviation = if stats.spread > 0. {
+                rng.gen_range(-stats.spread / 2.0..=stats.spread / 2.0)
+            } else {
+                0.
+            };
+            let bullet_direction = Vec2::from_angle(deviation).rotate(aim.direction);
+            let critical = rng.gen::<f32>() < stats.crit_chance;
+            let pellet_damage = if critical {
+                (damage as f32 * CRIT_MULTIPLIER).round() as i32
+            } else {
+                damage
+            };
+
+            spawn_bullet(
+                &mut commands,
+                player_position,
+                bullet_direction * stats.projectile_speed,
+                Bullet {
+                    owner: player_entity,
+                    faction: Faction::Player,
+                    lifetime: Timer::from_seconds(stats.lifetime, TimerMode::Once),
+                    damage: pellet_damage,
+                    critical,
+                    pierce: stats.pierce,
+                },
+            );
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use bevy::prelude::States;
+
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppState {
+    #[default]
+    MainMenu,
+    Loading,
+    Playing,
+    Paused,
+    GameOver,
+}
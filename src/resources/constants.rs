@@ -1 +1,47 @@
+use bevy::math::Vec2;
+
 pub const PLAYER_SPEED: f32 = 400.;
+
+pub const MAP_PATH: &str = "stare-bielany.tmj";
+pub const MAP_SCALE: f32 = 2.;
+pub const COLLISION_LAYER: &str = "collision";
+pub const SPAWNER_LAYER: &str = "spawners";
+pub const NAV_CELL_SIZE: f32 = 32.;
+/// Obstacles are inflated by this much on the nav grid so enemy colliders fit through the planned paths.
+pub const NAV_CLEARANCE: f32 = 25.;
+
+pub const PLAYER_MAX_HEALTH: i32 = 100;
+pub const PLAYER_INVULNERABILITY_SECONDS: f32 = 1.;
+pub const ENEMY_CONTACT_DAMAGE: i32 = 10;
+
+pub const ENEMY_REPATH_SECONDS: f32 = 0.5;
+/// Upper bound on A* searches per frame, shared by all enemies.
+pub const MAX_PATHS_PER_FRAME: usize = 4;
+pub const WAYPOINT_REACH: f32 = 16.;
+
+/// How fast a searching enemy sweeps its vision cone around, in radians per second.
+pub const ENEMY_SEARCH_TURN_SPEED: f32 = 1.5;
+pub const ENEMY_FLEE_DISTANCE: f32 = 300.;
+
+pub const CORPSE_SECONDS: f32 = 6.;
+pub const CORPSE_FALL_SECONDS: f32 = 0.3;
+pub const CORPSE_FADE_SECONDS: f32 = 1.5;
+/// How far apart the pickups dropped by one enemy land.
+pub const LOOT_SCATTER: f32 = 20.;
+
+pub const CRIT_MULTIPLIER: f32 = 2.;
+pub const ENEMY_HEALTH_BAR_SIZE: Vec2 = Vec2::new(40., 5.);
+pub const DAMAGE_NUMBER_SECONDS: f32 = 0.8;
+/// Upwards speed of floating damage numbers, in pixels per second.
+pub const DAMAGE_NUMBER_RISE_SPEED: f32 = 60.;
+
+/// Stick deflection below which gamepad sticks read as centred.
+pub const GAMEPAD_STICK_DEADZONE: f32 = 0.2;
+/// How far from the player the aim point sits when aiming with the right stick.
+pub const GAMEPAD_AIM_DISTANCE: f32 = 200.;
+/// Enemies further away than this do not pull the gamepad aim.
+pub const AIM_ASSIST_RANGE: f32 = 400.;
+/// Widest angle, in radians, between the stick aim and an enemy for the aim to bend towards it.
+pub const AIM_ASSIST_ANGLE: f32 = 0.25;
+/// Fraction of that angle the aim is bent by; 1 snaps straight onto the enemy.
+pub const AIM_ASSIST_STRENGTH: f32 = 0.6;
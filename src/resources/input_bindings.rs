@@ -0,0 +1,328 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::path::PathBuf;
+
+use bevy::input::gamepad::GamepadButtonType;
+use bevy::input::keyboard::KeyCode;
+use bevy::input::mouse::MouseButton;
+use bevy::prelude::Resource;
+use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::helpers::user_data::{config_dir, read_config, write_config};
+use crate::resources::locale::Locale;
+
+/// Something the player can do, bound to one or more physical inputs.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum InputAction {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Fire,
+    Reload,
+    Interact,
+    /// Accepts prompts such as the game over screen.
+    Confirm,
+    Pause,
+    NextWeapon,
+    PreviousWeapon,
+    Weapon1,
+    Weapon2,
+    Weapon3,
+    Weapon4,
+    PanUp,
+    PanDown,
+    PanLeft,
+    PanRight,
+    MenuUp,
+    MenuDown,
+    MenuLeft,
+    MenuRight,
+    MenuBack,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 24] = [
+        InputAction::MoveUp,
+        InputAction::MoveDown,
+        InputAction::MoveLeft,
+        InputAction::MoveRight,
+        InputAction::Fire,
+        InputAction::Reload,
+        InputAction::Interact,
+        InputAction::Confirm,
+        InputAction::Pause,
+        InputAction::NextWeapon,
+        InputAction::PreviousWeapon,
+        InputAction::Weapon1,
+        InputAction::Weapon2,
+        InputAction::Weapon3,
+        InputAction::Weapon4,
+        InputAction::PanUp,
+        InputAction::PanDown,
+        InputAction::PanLeft,
+        InputAction::PanRight,
+        InputAction::MenuUp,
+        InputAction::MenuDown,
+        InputAction::MenuLeft,
+        InputAction::MenuRight,
+        InputAction::MenuBack,
+    ];
+
+    pub const WEAPON_SLOTS: [InputAction; 4] =
+        [InputAction::Weapon1, InputAction::Weapon2, InputAction::Weapon3, InputAction::Weapon4];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            InputAction::MoveUp => "Move up",
+            InputAction::MoveDown => "Move down",
+            InputAction::MoveLeft => "Move left",
+            InputAction::MoveRight => "Move right",
+            InputAction::Fire => "Fire",
+            InputAction::Reload => "Reload",
+            InputAction::Interact => "Interact",
+            InputAction::Confirm => "Confirm",
+            InputAction::Pause => "Pause",
+            InputAction::NextWeapon => "Next weapon",
+            InputAction::PreviousWeapon => "Previous weapon",
+            InputAction::Weapon1 => "Weapon 1",
+            InputAction::Weapon2 => "Weapon 2",
+            InputAction::Weapon3 => "Weapon 3",
+            InputAction::Weapon4 => "Weapon 4",
+            InputAction::PanUp => "Pan camera up",
+            InputAction::PanDown => "Pan camera down",
+            InputAction::PanLeft => "Pan camera left",
+            InputAction::PanRight => "Pan camera right",
+            InputAction::MenuUp => "Menu up",
+            InputAction::MenuDown => "Menu down",
+            InputAction::MenuLeft => "Menu left",
+            InputAction::MenuRight => "Menu right",
+            InputAction::MenuBack => "Menu back",
+        }
+    }
+
+    /// Menu navigation keeps its default bindings, so a bad rebind cannot lock the player out of
+    /// the menu that would undo it.
+    pub fn is_rebindable(&self) -> bool {
+        !matches!(
+            self,
+            InputAction::MenuUp
+                | InputAction::MenuDown
+                | InputAction::MenuLeft
+                | InputAction::MenuRight
+                | InputAction::MenuBack
+        )
+    }
+
+    /// Actions listed on the Controls page, in menu order.
+    pub fn rebindable() -> impl Iterator<Item = InputAction> {
+        InputAction::ALL.into_iter().filter(InputAction::is_rebindable)
+    }
+}
+
+/// Keys that can be bound; anything else is ignored when rebinding.
+pub const BINDABLE_KEYS: [KeyCode; 61] = [
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G,
+    KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N,
+    KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U,
+    KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+    KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6,
+    KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+    KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right,
+    KeyCode::Space, KeyCode::Return, KeyCode::Tab, KeyCode::Back,
+    KeyCode::ShiftLeft, KeyCode::ShiftRight, KeyCode::ControlLeft, KeyCode::ControlRight,
+    KeyCode::AltLeft,
+];
+
+/// Keys only default bindings use. Escape cancels rebinding, so it cannot be bound from the menu,
+/// but a bindings file holding the default Pause binding still has to load.
+pub const RESERVED_KEYS: [KeyCode; 1] = [KeyCode::Escape];
+
+pub const BINDABLE_MOUSE_BUTTONS: [MouseButton; 3] = [MouseButton::Left, MouseButton::Right, MouseButton::Middle];
+
+pub const BINDABLE_GAMEPAD_BUTTONS: [GamepadButtonType; 16] = [
+    GamepadButtonType::South,
+    GamepadButtonType::East,
+    GamepadButtonType::North,
+    GamepadButtonType::West,
+    GamepadButtonType::LeftTrigger,
+    GamepadButtonType::LeftTrigger2,
+    GamepadButtonType::RightTrigger,
+    GamepadButtonType::RightTrigger2,
+    GamepadButtonType::Select,
+    GamepadButtonType::Start,
+    GamepadButtonType::LeftThumb,
+    GamepadButtonType::RightThumb,
+    GamepadButtonType::DPadUp,
+    GamepadButtonType::DPadDown,
+    GamepadButtonType::DPadLeft,
+    GamepadButtonType::DPadRight,
+];
+
+/// A physical input. Stored as text such as `key:W`, `mouse:Left`, `gamepad:South` or `wheel:up`,
+/// since Bevy's input types are not serializable without an extra feature.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButtonType),
+    /// One notch of the mouse wheel; `true` scrolls up.
+    Wheel(bool),
+}
+
+impl Binding {
+    pub fn is_gamepad(&self) -> bool {
+        matches!(self, Binding::Gamepad(_))
+    }
+}
+
+impl Display for Binding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{key:?}"),
+            Binding::Mouse(button) => write!(f, "Mouse {button:?}"),
+            Binding::Gamepad(button) => write!(f, "Pad {button:?}"),
+            Binding::Wheel(true) => write!(f, "Wheel up"),
+            Binding::Wheel(false) => write!(f, "Wheel down"),
+        }
+    }
+}
+
+impl From<Binding> for String {
+    fn from(binding: Binding) -> Self {
+        match binding {
+            Binding::Key(key) => format!("key:{key:?}"),
+            Binding::Mouse(button) => format!("mouse:{button:?}"),
+            Binding::Gamepad(button) => format!("gamepad:{button:?}"),
+            Binding::Wheel(up) => format!("wheel:{}", if up { "up" } else { "down" }),
+        }
+    }
+}
+
+impl TryFrom<String> for Binding {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        let binding = match text.split_once(':') {
+            Some(("key", name)) => find_by_name(&BINDABLE_KEYS, name)
+                .or_else(|| find_by_name(&RESERVED_KEYS, name))
+                .map(Binding::Key),
+            Some(("mouse", name)) => find_by_name(&BINDABLE_MOUSE_BUTTONS, name).map(Binding::Mouse),
+            Some(("gamepad", name)) => find_by_name(&BINDABLE_GAMEPAD_BUTTONS, name).map(Binding::Gamepad),
+            Some(("wheel", "up")) => Some(Binding::Wheel(true)),
+            Some(("wheel", "down")) => Some(Binding::Wheel(false)),
+            _ => None,
+        };
+
+        binding.ok_or_else(|| format!("unknown input binding `{text}`"))
+    }
+}
+
+fn find_by_name<T: Debug + Copy>(values: &[T], name: &str) -> Option<T> {
+    values.iter().copied().find(|value| format!("{value:?}") == name)
+}
+
+/// Which inputs trigger each action, stored in `bindings.json` in the config directory.
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq)]
+pub struct InputBindings(pub(crate) HashMap<InputAction, Vec<Binding>>);
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        use InputAction::*;
+
+        let key = Binding::Key;
+        let pad = Binding::Gamepad;
+        let bindings = [
+            (MoveUp, vec![key(KeyCode::W)]),
+            (MoveDown, vec![key(KeyCode::S)]),
+            (MoveLeft, vec![key(KeyCode::A)]),
+            (MoveRight, vec![key(KeyCode::D)]),
+            (Fire, vec![Binding::Mouse(MouseButton::Left), pad(GamepadButtonType::RightTrigger2)]),
+            (Reload, vec![key(KeyCode::R), pad(GamepadButtonType::North)]),
+            (Interact, vec![key(KeyCode::E), pad(GamepadButtonType::West)]),
+            (Confirm, vec![key(KeyCode::Return), key(KeyCode::Space), pad(GamepadButtonType::South)]),
+            (Pause, vec![key(KeyCode::Escape), pad(GamepadButtonType::Start)]),
+            (NextWeapon, vec![Binding::Wheel(true), pad(GamepadButtonType::RightTrigger)]),
+            (PreviousWeapon, vec![Binding::Wheel(false), pad(GamepadButtonType::LeftTrigger)]),
+            (Weapon1, vec![key(KeyCode::Key1)]),
+            (Weapon2, vec![key(KeyCode::Key2)]),
+            (Weapon3, vec![key(KeyCode::Key3)]),
+            (Weapon4, vec![key(KeyCode::Key4)]),
+            (PanUp, vec![key(KeyCode::Up)]),
+            (PanDown, vec![key(KeyCode::Down)]),
+            (PanLeft, vec![key(KeyCode::Left)]),
+            (PanRight, vec![key(KeyCode::Right)]),
+            (MenuUp, vec![key(KeyCode::Up), key(KeyCode::W), pad(GamepadButtonType::DPadUp)]),
+            (MenuDown, vec![key(KeyCode::Down), key(KeyCode::S), pad(GamepadButtonType::DPadDown)]),
+            (MenuLeft, vec![key(KeyCode::Left), key(KeyCode::A), pad(GamepadButtonType::DPadLeft)]),
+            (MenuRight, vec![key(KeyCode::Right), key(KeyCode::D), pad(GamepadButtonType::DPadRight)]),
+            (MenuBack, vec![key(KeyCode::Escape), pad(GamepadButtonType::East)]),
+        ];
+
+        InputBindings(bindings.into_iter().collect())
+    }
+}
+
+impl InputBindings {
+    pub fn path() -> PathBuf {
+        config_dir().join("bindings.json")
+    }
+
+    /// Reads the bindings file; actions missing from it keep their default bindings.
+    pub fn load() -> Self {
+        let mut bindings = InputBindings::default();
+        if let Some(saved) = read_config::<InputBindings>(&Self::path()) {
+            bindings.0.extend(saved.0);
+        }
+        bindings
+    }
+
+    pub fn save(&self) {
+        write_config(&Self::path(), self);
+    }
+
+    pub fn get(&self, action: InputAction) -> &[Binding] {
+        self.0.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Replaces the bindings of the same device kind as `binding`, so rebinding a key keeps the
+    /// gamepad button of the action and the other way round.
+    pub fn rebind(&mut self, action: InputAction, binding: Binding) {
+        let bindings = self.0.entry(action).or_default();
+        bindings.retain(|existing| existing.is_gamepad() != binding.is_gamepad());
+        bindings.push(binding);
+    }
+
+    pub fn label(&self, action: InputAction, locale: &Locale) -> String {
+        let bindings: Vec<String> = self.get(action).iter().map(Binding::to_string).collect();
+
+        if bindings.is_empty() {
+            format!("{}: -", locale.tr(action.name()))
+        } else {
+            format!("{}: {}", locale.tr(action.name()), bindings.join(" / "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_bindings_survive_a_round_trip() {
+        let bindings = InputBindings::default();
+        let json = serde_json::to_vec(&bindings).unwrap();
+
+        assert!(serde_json::from_slice::<InputBindings>(&json).unwrap() == bindings);
+    }
+
+    #[test]
+    fn escape_is_not_bindable() {
+        assert!(!BINDABLE_KEYS.contains(&KeyCode::Escape));
+        assert!(Binding::try_from("key:Escape".to_string()).is_ok());
+    }
+}
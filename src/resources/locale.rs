@@ -0,0 +1,114 @@
+use bevy::prelude::Resource;
+
+use crate::resources::settings::Language;
+
+/// Language the menus and the HUD are shown in, kept in step with `Settings::language`.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Locale(pub(crate) Language);
+
+impl Locale {
+    /// `text` in the current language. The English text doubles as the key, and text without a
+    /// translation is shown in English.
+    pub fn tr(&self, text: &'static str) -> &'static str {
+        let translations = match self.0 {
+            Language::English => return text,
+            Language::Polish => POLISH,
+        };
+
+        translations
+            .iter()
+            .find(|(english, _)| *english == text)
+            .map_or(text, |(_, translated)| *translated)
+    }
+}
+
+const POLISH: &[(&str, &str)] = &[
+    // Main menu
+    ("New Game", "Nowa gra"),
+    ("Continue", "Kontynuuj"),
+    ("Settings", "Ustawienia"),
+    ("Credits", "Twórcy"),
+    ("Quit", "Wyjdź"),
+    ("Back", "Wstecz"),
+    ("Controls", "Sterowanie"),
+    ("Reset to defaults", "Przywróć domyślne"),
+    ("Made with Bevy and Rapier", "Zrobione w Bevy i Rapier"),
+    ("Map drawn in Tiled", "Mapa narysowana w Tiled"),
+    // Settings
+    ("Resolution", "Rozdzielczość"),
+    ("Display", "Ekran"),
+    ("Windowed", "Okno"),
+    ("Borderless", "Bez ramki"),
+    ("Fullscreen", "Pełny ekran"),
+    ("Master volume", "Głośność ogólna"),
+    ("Music volume", "Głośność muzyki"),
+    ("Effects volume", "Głośność efektów"),
+    ("Mouse sensitivity", "Czułość myszy"),
+    ("Physics debug", "Debugowanie fizyki"),
+    ("Language", "Język"),
+    ("On", "Wł."),
+    ("Off", "Wył."),
+    // Controls
+    ("Move up", "Ruch w górę"),
+    ("Move down", "Ruch w dół"),
+    ("Move left", "Ruch w lewo"),
+    ("Move right", "Ruch w prawo"),
+    ("Fire", "Strzał"),
+    ("Reload", "Przeładowanie"),
+    ("Interact", "Użyj"),
+    ("Confirm", "Zatwierdź"),
+    ("Pause", "Pauza"),
+    ("Next weapon", "Następna broń"),
+    ("Previous weapon", "Poprzednia broń"),
+    ("Weapon 1", "Broń 1"),
+    ("Weapon 2", "Broń 2"),
+    ("Weapon 3", "Broń 3"),
+    ("Weapon 4", "Broń 4"),
+    ("Pan camera up", "Kamera w górę"),
+    ("Pan camera down", "Kamera w dół"),
+    ("Pan camera left", "Kamera w lewo"),
+    ("Pan camera right", "Kamera w prawo"),
+    ("press a key...", "naciśnij klawisz..."),
+    // State screens
+    ("Loading...", "Wczytywanie..."),
+    ("Paused\n\nPress Escape to resume", "Pauza\n\nNaciśnij Escape, aby wrócić do gry"),
+    ("Game over\n\nPress Enter to return to the menu", "Koniec gry\n\nNaciśnij Enter, aby wrócić do menu"),
+    // HUD
+    ("Pistol", "Pistolet"),
+    ("Shotgun", "Strzelba"),
+    ("Rifle", "Karabin"),
+    ("reloading...", "przeładowanie..."),
+    ("Score", "Wynik"),
+    ("Kills", "Zabici"),
+    ("Get ready", "Przygotuj się"),
+    ("All waves cleared", "Wszystkie fale odparte"),
+    ("Wave", "Fala"),
+    ("Enemies left", "Pozostali wrogowie"),
+    ("Speed", "Szybkość"),
+    ("Damage", "Obrażenia"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn english_text_is_its_own_translation() {
+        assert_eq!(Locale(Language::English).tr("New Game"), "New Game");
+    }
+
+    #[test]
+    fn polish_falls_back_to_english_without_a_translation() {
+        let locale = Locale(Language::Polish);
+
+        assert_eq!(locale.tr("New Game"), "Nowa gra");
+        assert_eq!(locale.tr("VSync"), "VSync");
+    }
+
+    #[test]
+    fn polish_translations_have_unique_keys() {
+        for (index, (english, _)) in POLISH.iter().enumerate() {
+            assert!(!POLISH[..index].iter().any(|(other, _)| other == english), "{english:?} is translated twice");
+        }
+    }
+}
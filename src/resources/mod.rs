@@ -1 +1,5 @@
+pub mod app_state;
 pub mod constants;
+pub mod settings;
+pub mod input_bindings;
+pub mod locale;
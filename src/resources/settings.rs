@@ -0,0 +1,127 @@
+use std::path::PathBuf;
+
+use bevy::prelude::{default, Resource};
+use bevy::window::{PresentMode, Window, WindowMode, WindowResolution};
+use serde::{Deserialize, Serialize};
+
+use crate::helpers::user_data::{config_dir, read_config, write_config};
+
+/// Window sizes offered in the settings menu.
+pub const RESOLUTIONS: [[u32; 2]; 4] = [[1280, 720], [1600, 900], [1920, 1080], [2560, 1440]];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DisplayMode {
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+impl DisplayMode {
+    pub const ALL: [DisplayMode; 3] = [DisplayMode::Windowed, DisplayMode::Borderless, DisplayMode::Fullscreen];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DisplayMode::Windowed => "Windowed",
+            DisplayMode::Borderless => "Borderless",
+            DisplayMode::Fullscreen => "Fullscreen",
+        }
+    }
+
+    pub fn window_mode(&self) -> WindowMode {
+        match self {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            DisplayMode::Borderless => WindowMode::BorderlessFullscreen,
+            DisplayMode::Fullscreen => WindowMode::Fullscreen,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Language {
+    #[default]
+    English,
+    Polish,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Polish];
+
+    /// Name of the language in itself, so it can be found whatever language is selected.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Polish => "Polski",
+        }
+    }
+}
+
+/// Player preferences, stored in `settings.json` in the config directory. Missing fields fall
+/// back to their defaults, so older files keep working.
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub(crate) resolution: [u32; 2],
+    pub(crate) display_mode: DisplayMode,
+    pub(crate) vsync: bool,
+    /// Volumes range from 0 to 1; music and effects are scaled by the master volume too.
+    pub(crate) master_volume: f32,
+    pub(crate) music_volume: f32,
+    pub(crate) effects_volume: f32,
+    /// Scales mouse motion before it moves the crosshair.
+    pub(crate) mouse_sensitivity: f32,
+    pub(crate) show_physics_debug: bool,
+    pub(crate) language: Language,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            resolution: RESOLUTIONS[0],
+            display_mode: DisplayMode::Windowed,
+            vsync: true,
+            master_volume: 1.,
+            music_volume: 0.8,
+            effects_volume: 0.8,
+            mouse_sensitivity: 1.,
+            show_physics_debug: false,
+            language: Language::English,
+        }
+    }
+}
+
+impl Settings {
+    pub fn path() -> PathBuf {
+        config_dir().join("settings.json")
+    }
+
+    /// Reads the settings file, using the defaults when it is missing or broken.
+    pub fn load() -> Self {
+        read_config(&Self::path()).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        write_config(&Self::path(), self);
+    }
+
+    pub fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+
+    /// Primary window as configured, for the `WindowPlugin` at startup.
+    pub fn window(&self) -> Window {
+        let [width, height] = self.resolution;
+
+        Window {
+            resolution: WindowResolution::new(width as f32, height as f32),
+            mode: self.display_mode.window_mode(),
+            present_mode: self.present_mode(),
+            ..default()
+        }
+    }
+}
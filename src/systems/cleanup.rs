@@ -0,0 +1,10 @@
+use bevy::prelude::{Commands, Component, DespawnRecursiveExt, Entity, Query, With};
+
+pub fn despawn_with<T: Component>(
+    mut commands: Commands,
+    query: Query<Entity, With<T>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -1 +1 @@
-
+pub mod cleanup;